@@ -0,0 +1,37 @@
+//! Measures how many complete games per second the engine can play.
+//!
+//! Run with `cargo bench`. Every player uses the `Cheater` strategy, since it's cheap to evaluate
+//! and plays long games, so most of the time should be spent in the engine itself.
+
+use hanabot::hanabi::strategy::{Cheater, Strategy};
+use hanabot::hanabi::Game;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::time::Instant;
+
+const GAMES: u64 = 100_000;
+
+fn main() {
+    for players in 2..=5 {
+        let names: Vec<_> = (0..players).map(|p| p.to_string()).collect();
+        let mut strategies: Vec<Box<dyn Strategy>> = (0..players)
+            .map(|_| Box::new(Cheater) as Box<dyn Strategy>)
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(players as u64);
+        let mut points = 0;
+        let start = Instant::now();
+        for _ in 0..GAMES {
+            let mut game = Game::with_rng(names.iter().map(|s| &**s), &mut rng);
+            points += game.play_out(&mut strategies);
+        }
+        let took = start.elapsed();
+
+        println!(
+            "{players} players: {GAMES} games in {:.2?} ({:.0} games/s, mean score {:.2})",
+            took,
+            GAMES as f64 / took.as_secs_f64(),
+            points as f64 / GAMES as f64,
+        );
+    }
+}
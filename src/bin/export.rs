@@ -0,0 +1,34 @@
+use eyre::Context;
+use hanabot::Hanabi;
+
+const USAGE: &str = "\
+usage: export GAME
+
+Prints the finished game numbered GAME in the JSON format used by hanab.live, reading the bot's
+saved state from state.json in the current directory.";
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let (Some(game), None) = (args.next(), args.next()) else {
+        eyre::bail!("{USAGE}");
+    };
+    if game == "-h" || game == "--help" {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    let game: usize = game
+        .trim_start_matches('#')
+        .parse()
+        .context("parse game number")?;
+
+    let hanabi = Hanabi::resume()
+        .await
+        .context("resume from saved game states")?
+        .ok_or_else(|| eyre::eyre!("there is no state.json in the current directory"))?;
+    match hanabi.export(game)? {
+        Some(json) => println!("{json}"),
+        None => eyre::bail!("game #{game} has not ended, or does not exist"),
+    }
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use eyre::Context;
+use hanabot::hanabi::strategy::{Cheater, Heuristic, Random, Strategy};
+use hanabot::hanabi::Game;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const USAGE: &str = "\
+usage: simulate [--games N] [--seed S] [--players P] [--strategy random|heuristic|cheater]
+
+Plays N seeded games (default 1000) for each player count (default 2 through 5), with every player
+using the given strategy (default random), and reports how well they did.";
+
+fn main() -> eyre::Result<()> {
+    let mut games = 1000;
+    let mut seed = 0;
+    let mut players = 2..=5;
+    let mut strategy = String::from("random");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| eyre::eyre!("{arg} requires a value\n\n{USAGE}"))
+        };
+        match &*arg {
+            "--games" => games = value()?.parse().context("parse --games")?,
+            "--seed" => seed = value()?.parse().context("parse --seed")?,
+            "--players" => {
+                let n = value()?.parse().context("parse --players")?;
+                if !(2..=5).contains(&n) {
+                    eyre::bail!("hanabi is played with 2 to 5 players");
+                }
+                players = n..=n;
+            }
+            "--strategy" => strategy = value()?,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ => eyre::bail!("unknown argument {arg}\n\n{USAGE}"),
+        }
+    }
+
+    if make_strategy(&strategy, 0).is_none() {
+        eyre::bail!("unknown strategy {strategy}\n\n{USAGE}");
+    }
+
+    for nplayers in players {
+        let names: Vec<_> = (0..nplayers).map(|p| p.to_string()).collect();
+        let mut scores = Vec::with_capacity(games);
+        let mut struck_out = 0;
+        for g in 0..games as u64 {
+            let game_seed = seed ^ (g << 8) ^ nplayers as u64;
+            let mut game = Game::with_rng(
+                names.iter().map(|s| &**s),
+                &mut StdRng::seed_from_u64(game_seed),
+            );
+            let mut strategies: Vec<_> = (0..nplayers as u64)
+                .map(|p| make_strategy(&strategy, game_seed + p + 1).unwrap())
+                .collect();
+
+            scores.push(game.play_out(&mut strategies));
+            if game.lives() == 0 {
+                struck_out += 1;
+            }
+        }
+
+        report(nplayers, &mut scores, struck_out);
+    }
+
+    Ok(())
+}
+
+/// Construct the strategy named `name`, using `seed` for any randomness it needs.
+fn make_strategy(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    match name {
+        "random" => Some(Box::new(Random::new(StdRng::seed_from_u64(seed)))),
+        "heuristic" => Some(Box::new(Heuristic)),
+        "cheater" => Some(Box::new(Cheater)),
+        _ => None,
+    }
+}
+
+/// Print summary statistics for the `scores` of a set of games with `nplayers` players.
+fn report(nplayers: usize, scores: &mut [usize], struck_out: usize) {
+    if scores.is_empty() {
+        return;
+    }
+
+    scores.sort_unstable();
+    let n = scores.len();
+    let mean = scores.iter().sum::<usize>() as f64 / n as f64;
+    let median = if n % 2 == 0 {
+        (scores[n / 2 - 1] + scores[n / 2]) as f64 / 2.0
+    } else {
+        scores[n / 2] as f64
+    };
+    let perfect = scores.iter().filter(|&&s| s == 25).count();
+
+    println!("{nplayers} players, {n} games:");
+    println!("  mean score:    {mean:.2}");
+    println!("  median score:  {median:.1}");
+    println!("  perfect games: {:.2}%", 100.0 * perfect as f64 / n as f64);
+    println!(
+        "  struck out:    {:.2}%",
+        100.0 * struck_out as f64 / n as f64
+    );
+
+    let mut histogram = [0usize; 26];
+    for &score in &*scores {
+        histogram[score] += 1;
+    }
+    let most = *histogram.iter().max().unwrap();
+    let width = 50;
+    for (score, &count) in histogram.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let bar = (width * count).div_ceil(most);
+        println!("  {score:>2} | {} {count}", "#".repeat(bar));
+    }
+    println!();
+}
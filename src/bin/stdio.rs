@@ -10,6 +10,10 @@ async fn main() -> eyre::Result<()> {
         .await
         .context("resume from saved game states")?
         .unwrap_or_default();
+    hanabi
+        .load_puzzles("puzzles.json")
+        .await
+        .context("load puzzles")?;
 
     let stdin = tokio::io::BufReader::new(tokio::io::stdin());
     let mut lines = stdin.lines();
@@ -0,0 +1,161 @@
+//! Looking back over a game for moves worth talking about.
+//!
+//! Many groups play with the [H-group conventions](https://hanabi.github.io/), and the checks here
+//! flag moves those conventions (and common sense) say are likely mistakes. They can't know what
+//! the players were thinking, so they only point out moments worth a second look. Some mistakes,
+//! like discarding with all eight clue tokens, can't happen at all, since the game forbids them.
+
+use super::record::Record;
+use super::{Action, ActionError, Clue, Color, Number};
+use std::collections::HashSet;
+
+/// A move that was likely a mistake.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mistake {
+    /// A clue to the given player that didn't touch any new cards or tell them anything new about
+    /// the ones it touched.
+    Redundant { player: usize, clue: Clue },
+    /// A clue that touched a card, in the given player's hand, that could never be played.
+    TouchedTrash {
+        player: usize,
+        card: (Color, Number),
+    },
+    /// A card that had been clued was discarded while it was still needed.
+    DiscardedClued((Color, Number)),
+    /// The last copy of a card was discarded before it could be played.
+    DiscardedCritical((Color, Number)),
+    /// A card was discarded that could have been played right away.
+    DiscardedPlayable((Color, Number)),
+    /// A card that had been clued was played, but wasn't playable.
+    Misplayed((Color, Number)),
+    /// A card that had never been clued was played, but wasn't playable.
+    ///
+    /// This usually means the player thought they were being finessed.
+    BlindMisplayed((Color, Number)),
+}
+
+/// A likely mistake, and when it was made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Moment {
+    /// The turn the move was made on, counting from 1.
+    ///
+    /// This is also the turn to `review` to see the game right after the move.
+    pub turn: usize,
+    /// The hand index of the player who made the move.
+    pub player: usize,
+    pub mistake: Mistake,
+}
+
+impl Moment {
+    /// Describe the moment to players, given the names of everyone in the game in turn order.
+    pub fn describe(&self, players: &[String]) -> String {
+        let who = &players[self.player];
+        let what = match self.mistake {
+            Mistake::Redundant { player, clue } => format!(
+                "<@{who}> clued <@{}> {clue}, which told them nothing new.",
+                players[player]
+            ),
+            Mistake::TouchedTrash {
+                player,
+                card: (c, n),
+            } => format!(
+                "<@{who}>'s clue touched <@{}>'s {c} {n}, which could never be played.",
+                players[player]
+            ),
+            Mistake::DiscardedClued((c, n)) => {
+                format!("<@{who}> discarded a clued {c} {n} that was still needed.")
+            }
+            Mistake::DiscardedCritical((c, n)) => {
+                format!("<@{who}> discarded the last {c} {n}, so that stack can't be finished.")
+            }
+            Mistake::DiscardedPlayable((c, n)) => {
+                format!("<@{who}> discarded {c} {n}, which was playable.")
+            }
+            Mistake::Misplayed((c, n)) => {
+                format!("<@{who}> misplayed a clued {c} {n}.")
+            }
+            Mistake::BlindMisplayed((c, n)) => format!(
+                "<@{who}> played an unclued {c} {n} that wasn't playable. Was it a finesse?"
+            ),
+        };
+        format!("Turn {}: {what}", self.turn)
+    }
+}
+
+/// Find the likely mistakes in a recorded game, in the order they were made.
+///
+/// Fails if the record can't be replayed.
+pub fn notable_moments(record: &Record) -> Result<Vec<Moment>, ActionError> {
+    let mut game = record.replay(0)?;
+    let mut moments = Vec::new();
+    // the ids of every card that has been touched by a clue
+    let mut touched = HashSet::new();
+
+    for (i, &action) in record.actions.iter().enumerate() {
+        let player = game.turn;
+        let mut flag = |mistake| {
+            moments.push(Moment {
+                turn: i + 1,
+                player,
+                mistake,
+            })
+        };
+
+        match action {
+            Action::Clue { player: to, clue } => {
+                let mut news = false;
+                for card in game.hands[to].cards() {
+                    if !clue.touches(card.color, card.number) {
+                        continue;
+                    }
+                    let mut knowledge = card.knowledge;
+                    knowledge.clued(clue, true);
+                    news |= knowledge != card.knowledge;
+                    if touched.insert(card.id) {
+                        news = true;
+                        if game.is_trash(card.color, card.number) {
+                            flag(Mistake::TouchedTrash {
+                                player: to,
+                                card: (card.color, card.number),
+                            });
+                        }
+                    }
+                }
+                if !news {
+                    flag(Mistake::Redundant { player: to, clue });
+                }
+            }
+            Action::Play(slot) => {
+                let card = game.hands[player]
+                    .get(slot)
+                    .ok_or(ActionError::NoSuchCard)?;
+                if !game.is_playable(card.color, card.number) {
+                    if touched.contains(&card.id) {
+                        flag(Mistake::Misplayed((card.color, card.number)));
+                    } else {
+                        flag(Mistake::BlindMisplayed((card.color, card.number)));
+                    }
+                }
+            }
+            Action::Discard(slot) => {
+                let card = game.hands[player]
+                    .get(slot)
+                    .ok_or(ActionError::NoSuchCard)?;
+                let identity = (card.color, card.number);
+                if game.is_trash(card.color, card.number) {
+                    // nothing lost
+                } else if game.is_playable(card.color, card.number) {
+                    flag(Mistake::DiscardedPlayable(identity));
+                } else if touched.contains(&card.id) {
+                    flag(Mistake::DiscardedClued(identity));
+                } else if game.is_critical(card.color, card.number) {
+                    flag(Mistake::DiscardedCritical(identity));
+                }
+            }
+        }
+
+        game.apply(action)?;
+    }
+
+    Ok(moments)
+}
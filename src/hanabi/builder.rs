@@ -0,0 +1,235 @@
+//! Setting up games in a particular position.
+//!
+//! Normal games start from a shuffled deck, which makes it hard to test what happens in any given
+//! situation, or to pose a puzzle. A [`Builder`] instead lets you say exactly which cards are
+//! where, what the players know, and how many tokens are left.
+
+use super::components::{Deck, Hand};
+use super::{Clue, Color, Game, Number, COLOR_ORDER, NUMBERS};
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+/// The reasons a position can't be set up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    /// Games must have between 2 and 5 players.
+    Players(usize),
+    /// There is no player with this index.
+    NoSuchPlayer(usize),
+    /// This player holds more cards than a hand can in a game with this many players.
+    HandTooBig(usize),
+    /// More copies of this card were placed than there are in the deck.
+    TooManyCopies(Color, Number),
+    /// There can be at most 8 clue tokens.
+    TooManyClues,
+    /// There must be between 1 and 3 lives left.
+    Lives,
+}
+
+/// Sets up a game in a particular position.
+///
+/// Any card that isn't placed in a hand, in the deck, on a stack, or in the discard pile is added
+/// to the discard pile, so that every card is accounted for.
+///
+/// Games built this way don't start from a deal, so they can't be replayed from a
+/// [`Record`](super::record::Record).
+#[derive(Clone, Debug)]
+pub struct Builder {
+    players: Vec<String>,
+    hands: BTreeMap<usize, Vec<(Color, Number)>>,
+    deck: Vec<(Color, Number)>,
+    played: [Option<Number>; 5],
+    discard: Vec<(Color, Number)>,
+    clued: Vec<(usize, Clue)>,
+    clues: usize,
+    lives: usize,
+    turn: usize,
+}
+
+impl Game {
+    /// Start setting up a game in a particular position for the given players.
+    ///
+    /// Until told otherwise, every hand and the deck are empty, nothing has been played, and
+    /// there are 8 clue tokens and 3 lives.
+    pub fn builder<'a>(players: impl IntoIterator<Item = &'a str>) -> Builder {
+        let players: Vec<_> = players.into_iter().map(String::from).collect();
+        Builder {
+            players,
+            hands: BTreeMap::new(),
+            deck: Vec::new(),
+            played: [None; 5],
+            discard: Vec::new(),
+            clued: Vec::new(),
+            clues: 8,
+            lives: 3,
+            turn: 0,
+        }
+    }
+}
+
+impl Builder {
+    /// Give the `player`'th player these cards, from left to right.
+    pub fn hand(mut self, player: usize, cards: impl IntoIterator<Item = (Color, Number)>) -> Self {
+        self.hands.insert(player, cards.into_iter().collect());
+        self
+    }
+
+    /// Leave these cards in the deck, in the order they will be drawn.
+    pub fn deck(mut self, cards: impl IntoIterator<Item = (Color, Number)>) -> Self {
+        self.deck = cards.into_iter().collect();
+        self
+    }
+
+    /// Play every card of the given color up to and including `number`.
+    pub fn played(mut self, color: Color, number: Number) -> Self {
+        self.played[color as usize] = Some(number);
+        self
+    }
+
+    /// Put these cards in the discard pile.
+    pub fn discarded(mut self, cards: impl IntoIterator<Item = (Color, Number)>) -> Self {
+        self.discard.extend(cards);
+        self
+    }
+
+    /// Tell the `player`'th player about every card in their hand that `clue` touches, and that
+    /// the others aren't.
+    ///
+    /// This does not use up a clue token.
+    pub fn clued(mut self, player: usize, clue: Clue) -> Self {
+        self.clued.push((player, clue));
+        self
+    }
+
+    /// Set the number of clue tokens left.
+    pub fn clues(mut self, clues: usize) -> Self {
+        self.clues = clues;
+        self
+    }
+
+    /// Set the number of lives left.
+    pub fn lives(mut self, lives: usize) -> Self {
+        self.lives = lives;
+        self
+    }
+
+    /// Make it the `player`'th player's turn.
+    pub fn turn(mut self, player: usize) -> Self {
+        self.turn = player;
+        self
+    }
+
+    /// Set up the game.
+    ///
+    /// If the deck is empty, the game is in its last round, and every player gets one more turn.
+    pub fn build(self) -> Result<Game, BuildError> {
+        let nplayers = self.players.len();
+        if !(2..=5).contains(&nplayers) {
+            return Err(BuildError::Players(nplayers));
+        }
+        let players = self
+            .hands
+            .keys()
+            .copied()
+            .chain(self.clued.iter().map(|&(player, _)| player))
+            .chain([self.turn]);
+        if let Some(player) = players.into_iter().find(|&p| p >= nplayers) {
+            return Err(BuildError::NoSuchPlayer(player));
+        }
+        let max_hand = if nplayers <= 3 { 5 } else { 4 };
+        if let Some((&player, _)) = self.hands.iter().find(|(_, h)| h.len() > max_hand) {
+            return Err(BuildError::HandTooBig(player));
+        }
+        if self.clues > 8 {
+            return Err(BuildError::TooManyClues);
+        }
+        if !(1..=3).contains(&self.lives) {
+            return Err(BuildError::Lives);
+        }
+
+        // work out which cards haven't been placed anywhere
+        let mut unplaced = [[0; 5]; 5];
+        for color in COLOR_ORDER {
+            for number in NUMBERS {
+                unplaced[color as usize][number.as_usize() - 1] = number.copies();
+            }
+        }
+        let on_stacks = COLOR_ORDER.iter().flat_map(|&color| {
+            let top = self.played[color as usize].map_or(0, |n| n.as_usize());
+            NUMBERS[..top].iter().map(move |&number| (color, number))
+        });
+        let placed = self
+            .hands
+            .values()
+            .flatten()
+            .chain(&self.deck)
+            .chain(&self.discard)
+            .copied()
+            .chain(on_stacks);
+        for (color, number) in placed {
+            let left = &mut unplaced[color as usize][number.as_usize() - 1];
+            *left = left
+                .checked_sub(1)
+                .ok_or(BuildError::TooManyCopies(color, number))?;
+        }
+
+        // deal out the hands from a deck that holds them followed by the actual deck, so that
+        // every card gets a distinct id
+        let mut deck = Deck::from_order(
+            self.hands
+                .values()
+                .flatten()
+                .chain(&self.deck)
+                .copied()
+                .collect::<Vec<_>>(),
+        );
+        let mut hands = Vec::with_capacity(nplayers);
+        for (i, player) in self.players.iter().enumerate() {
+            let mut hand = Hand::new(player);
+            for _ in self.hands.get(&i).into_iter().flatten() {
+                hand.draw(&mut deck, 0);
+            }
+            hands.push(hand);
+        }
+        let mut setup_clues = Vec::with_capacity(self.clued.len());
+        for &(player, clue) in &self.clued {
+            hands[player].clue(clue);
+            setup_clues.push((clue, hands[player].cards().map(|card| card.id).collect()));
+        }
+
+        let mut discard = [[0; 5]; 5];
+        for &(color, number) in &self.discard {
+            discard[color as usize][number.as_usize() - 1] += 1;
+        }
+        for color in COLOR_ORDER {
+            for number in NUMBERS {
+                let left = unplaced[color as usize][number.as_usize() - 1];
+                discard[color as usize][number.as_usize() - 1] += left as u8;
+            }
+        }
+
+        let mut game = Game {
+            last_turns: deck.is_empty().then_some(0),
+            deck,
+            hands,
+            played: self.played,
+            discard,
+            last_move: None,
+            last_move_at: SystemTime::now(),
+            clues: self.clues,
+            lives: self.lives,
+            turn: self.turn,
+            first_turn: self.turn,
+            clues_given: 0,
+            started: SystemTime::now(),
+            max_score: 25,
+            dealt: Vec::new(),
+            history: Vec::new(),
+            notes: Default::default(),
+            open_hands: false,
+            setup_clues,
+        };
+        game.max_score = game.max_score();
+        Ok(game)
+    }
+}
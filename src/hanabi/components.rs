@@ -1,29 +1,27 @@
 use rand::seq::SliceRandom;
-use std::collections::LinkedList;
+use rand::Rng;
 
-/// An error that occurred while giving a clue.
-pub(crate) enum ClueError {
+/// The reason an [`Action`] cannot be taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionError {
+    /// The game has already ended.
+    GameOver,
+    /// The clue was directed at a player who is not in the game.
     NoSuchPlayer,
+    /// The clue was directed at the player whose turn it is.
+    ClueToSelf,
+    /// The clue does not touch any of the receiving player's cards.
     NoMatchingCards,
+    /// There are no clue tokens left to give a clue with.
     NotEnoughClues,
-    GameOver,
-}
-
-/// An error that occurred while giving playing a card.
-pub(crate) enum PlayError {
-    NoSuchCard,
-    GameOver,
-}
-
-/// An error that occurred while giving discarding a card.
-pub(crate) enum DiscardError {
-    NoSuchCard,
+    /// All the clue tokens are available, so discarding is not allowed.
     MaxClues,
-    GameOver,
+    /// The player does not have a card in the given position.
+    NoSuchCard,
 }
 
-#[derive(Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
-pub(crate) enum Color {
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Color {
     Red,
     Green,
     White,
@@ -44,8 +42,8 @@ impl fmt::Display for Color {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub(crate) enum Number {
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Number {
     One,
     Two,
     Three,
@@ -66,7 +64,7 @@ impl fmt::Display for Number {
 }
 
 impl Number {
-    pub(super) fn as_usize(&self) -> usize {
+    pub fn as_usize(&self) -> usize {
         match *self {
             Number::One => 1,
             Number::Two => 2,
@@ -75,6 +73,15 @@ impl Number {
             Number::Five => 5,
         }
     }
+
+    /// The number of cards with this number there are of each color.
+    pub fn copies(&self) -> usize {
+        match *self {
+            Number::One => 3,
+            Number::Five => 1,
+            _ => 2,
+        }
+    }
 }
 
 use serde::{Deserialize, Serialize};
@@ -97,12 +104,22 @@ impl Add<usize> for Number {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
-pub(crate) enum Clue {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Clue {
     Color(Color),
     Number(Number),
 }
 
+impl Clue {
+    /// Returns true if this clue touches a card of the given color and number.
+    pub fn touches(&self, color: Color, number: Number) -> bool {
+        match *self {
+            Clue::Color(c) => c == color,
+            Clue::Number(n) => n == number,
+        }
+    }
+}
+
 impl fmt::Display for Clue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -112,14 +129,201 @@ impl fmt::Display for Clue {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+/// A move a player can make on their turn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Give `clue` to the player with the given hand index.
+    Clue { player: usize, clue: Clue },
+    /// Play the `n`th card from the left (0-indexed).
+    Play(usize),
+    /// Discard the `n`th card from the left (0-indexed).
+    Discard(usize),
+}
+
+/// What a player knows about the identity of one of their cards from the clues they've been given.
+///
+/// Every clue tells the player either that a card *is* a given color or number, or that it is
+/// *not*, so the set of identities a card may have is always some set of colors crossed with some
+/// set of numbers. We store each as a bitset, with bit `i` set if the color at `COLOR_ORDER[i]`
+/// (or the number `i + 1`) is still possible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Knowledge {
+    colors: u8,
+    numbers: u8,
+}
+
+impl Default for Knowledge {
+    fn default() -> Self {
+        Knowledge {
+            colors: 0b11111,
+            numbers: 0b11111,
+        }
+    }
+}
+
+impl Knowledge {
+    /// Update what is known given that `clue` did (or did not) touch this card.
+    pub(super) fn clued(&mut self, clue: Clue, touched: bool) {
+        let (bits, bit) = match clue {
+            Clue::Color(c) => (&mut self.colors, 1 << c as u8),
+            Clue::Number(n) => (&mut self.numbers, 1 << (n.as_usize() - 1)),
+        };
+        if touched {
+            *bits &= bit;
+        } else {
+            *bits &= !bit;
+        }
+    }
+
+    /// Returns true if the card could still be the given color.
+    pub fn may_be_color(&self, color: Color) -> bool {
+        self.colors & (1 << color as u8) != 0
+    }
+
+    /// Returns true if the card could still be the given number.
+    pub fn may_be_number(&self, number: Number) -> bool {
+        self.numbers & (1 << (number.as_usize() - 1)) != 0
+    }
+
+    /// Returns true if the card could still have the given identity.
+    pub fn may_be(&self, color: Color, number: Number) -> bool {
+        self.may_be_color(color) && self.may_be_number(number)
+    }
+
+    /// The colors the card may still be.
+    pub fn colors(&self) -> impl Iterator<Item = Color> + use<> {
+        let k = *self;
+        super::COLOR_ORDER
+            .into_iter()
+            .filter(move |&c| k.may_be_color(c))
+    }
+
+    /// The numbers the card may still be.
+    pub fn numbers(&self) -> impl Iterator<Item = Number> + use<> {
+        let k = *self;
+        super::NUMBERS
+            .into_iter()
+            .filter(move |&n| k.may_be_number(n))
+    }
+
+    /// The card's color, if it is known.
+    pub fn color(&self) -> Option<Color> {
+        let mut colors = self.colors();
+        match (colors.next(), colors.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// The card's number, if it is known.
+    pub fn number(&self) -> Option<Number> {
+        let mut numbers = self.numbers();
+        match (numbers.next(), numbers.next()) {
+            (Some(n), None) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+/// A set of positions in a player's hand (0-indexed).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Slots(u8);
+
+impl Slots {
+    pub(super) fn insert(&mut self, slot: usize) {
+        self.0 |= 1 << slot;
+    }
+
+    pub fn contains(&self, slot: usize) -> bool {
+        slot < 8 && self.0 & (1 << slot) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The slots in this set, from left to right.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + use<> {
+        let bits = self.0;
+        (0..8).filter(move |slot| bits & (1 << slot) != 0)
+    }
+}
+
+impl fmt::Display for Slots {
+    /// Lists the slots as a player would refer to them, e.g. "slots 1, 2 and 4".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let slots: Vec<_> = self.iter().map(|slot| (slot + 1).to_string()).collect();
+        match &*slots {
+            [] => write!(f, "no slots"),
+            [one] => write!(f, "slot {one}"),
+            [rest @ .., last] => write!(f, "slots {} and {last}", rest.join(", ")),
+        }
+    }
+}
+
+/// The direct effect of an [`Action`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Effect {
+    /// The `player`'th player was given `clue`, which touched the cards in `touched`.
+    Clued {
+        player: usize,
+        clue: Clue,
+        touched: Slots,
+    },
+    /// The card was successfully added to its stack.
+    Played { card: (Color, Number) },
+    /// The card could not be played, so it was discarded and a life was lost.
+    Misplayed { card: (Color, Number) },
+    /// The card was discarded.
+    Discarded { card: (Color, Number) },
+}
+
+/// Why a game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameOver {
+    /// The team ran out of lives.
+    OutOfLives,
+    /// All the stacks were completed.
+    Perfect,
+    /// The deck ran out, and every player has had their last turn.
+    LastRoundOver,
+}
+
+/// Everything that happened as the result of a player taking an [`Action`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnOutcome {
+    /// The hand index of the player who took the action.
+    pub player: usize,
+    /// What the action did.
+    pub effect: Effect,
+    /// The card the player drew to replace the one they played or discarded, if any.
+    pub drew: Option<(Color, Number)>,
+    /// True if a clue token was regained, either by discarding or by completing a stack.
+    pub clue_refunded: bool,
+    /// True if this turn drew the last card from the deck, which starts the last round.
+    pub last_round_started: bool,
+    /// Set if this turn ended the game.
+    pub game_over: Option<GameOver>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub(super) struct Card {
+    /// Identifies this physical card for the rest of the game, wherever it moves to.
+    ///
+    /// Cards are numbered in the order they are drawn from the deck, starting at 0.
+    pub(super) id: usize,
     pub(super) color: Color,
     pub(super) number: Number,
 
-    /// All clues given to a player while this card was in their hand.
-    /// The `usize` is the hand index of the player who gave each clue.
-    pub(super) clues: Vec<(usize, Clue)>,
+    /// What the holder of this card knows about it from the clues they've been given.
+    pub(super) knowledge: Knowledge,
+
+    /// The number of actions that had been taken in the game when this card was drawn.
+    pub(super) drawn: usize,
 }
 
 impl fmt::Display for Card {
@@ -129,22 +333,40 @@ impl fmt::Display for Card {
 }
 
 impl Card {
+    /// Describe what the holder of this card knows about it.
+    ///
+    /// For any part of the card's identity that isn't known yet, this also lists what the card has
+    /// been ruled out to be, e.g. `:rainbow: :keycap_star: (not :heart:, not :one:)`.
     pub fn known(&self) -> String {
-        let know_color = self.clues.iter().any(|&(_, clue)| match clue {
-            Clue::Color(ref c) => c == &self.color,
-            _ => false,
-        });
-        let know_number = self.clues.iter().any(|&(_, clue)| match clue {
-            Clue::Number(ref n) => n == &self.number,
-            _ => false,
-        });
+        let k = &self.knowledge;
+        let mut known = match (k.color(), k.number()) {
+            (None, None) => ":rainbow: :keycap_star:".to_string(),
+            (None, Some(n)) => format!(":rainbow: {n}"),
+            (Some(c), None) => format!("{c} :keycap_star:"),
+            (Some(c), Some(n)) => format!("{c} {n}"),
+        };
 
-        match (know_color, know_number) {
-            (false, false) => ":rainbow: :keycap_star:".to_string(),
-            (false, true) => format!(":rainbow: {}", self.number),
-            (true, false) => format!("{} :keycap_star:", self.color),
-            (true, true) => format!("{} {}", self.color, self.number),
+        let mut not = Vec::new();
+        if k.color().is_none() {
+            not.extend(
+                super::COLOR_ORDER
+                    .iter()
+                    .filter(|&&c| !k.may_be_color(c))
+                    .map(|c| format!("not {c}")),
+            );
+        }
+        if k.number().is_none() {
+            not.extend(
+                super::NUMBERS
+                    .iter()
+                    .filter(|&&n| !k.may_be_number(n))
+                    .map(|n| format!("not {n}")),
+            );
+        }
+        if !not.is_empty() {
+            known.push_str(&format!(" ({})", not.join(", ")));
         }
+        known
     }
 }
 
@@ -167,10 +389,16 @@ impl Deck {
     pub(super) fn draw(&mut self) -> Option<Card> {
         self.1.pop()
     }
+
+    /// The cards left in the deck, in the order they will be drawn.
+    pub(super) fn cards(&self) -> impl Iterator<Item = &Card> {
+        self.1.iter().rev()
+    }
 }
 
-impl Default for Deck {
-    fn default() -> Self {
+impl Deck {
+    /// A full deck, shuffled using `rng`.
+    pub(super) fn shuffled(rng: &mut (impl Rng + ?Sized)) -> Self {
         let numbers = [
             Number::One,
             Number::One,
@@ -185,67 +413,104 @@ impl Default for Deck {
         ];
         let mut cards: Vec<_> = super::COLOR_ORDER
             .iter()
-            .flat_map(|&color| {
-                numbers.iter().map(move |&number| Card {
-                    color,
-                    number,
-                    clues: Vec::new(),
-                })
-            })
+            .flat_map(|&color| numbers.iter().map(move |&number| (color, number)))
             .collect();
 
-        cards.shuffle(&mut rand::rng());
+        cards.shuffle(rng);
+        // cards are drawn from the back
+        Deck::from_order(cards.into_iter().rev())
+    }
+
+    /// A deck that will deal the given cards in order.
+    pub(super) fn from_order(cards: impl IntoIterator<Item = (Color, Number)>) -> Self {
+        let mut cards: Vec<_> = cards
+            .into_iter()
+            .enumerate()
+            .map(|(id, (color, number))| Card {
+                id,
+                color,
+                number,
+                knowledge: Knowledge::default(),
+                drawn: 0,
+            })
+            .collect();
+        cards.reverse();
         Deck(cards.len(), cards)
     }
 }
 
+impl Default for Deck {
+    fn default() -> Self {
+        Deck::shuffled(&mut rand::rng())
+    }
+}
+
+/// The most cards a player can hold.
+const MAX_HAND: usize = 5;
+
 #[derive(Serialize, Deserialize)]
 pub(super) struct Hand {
     pub(super) player: String,
-    pub(super) cards: LinkedList<Card>,
+    cards: [Option<Card>; MAX_HAND],
+    len: usize,
 }
 
 impl Hand {
     pub(super) fn new(player: &str) -> Self {
         Hand {
             player: String::from(player),
-            cards: LinkedList::default(),
+            cards: [None; MAX_HAND],
+            len: 0,
         }
     }
 
-    pub(super) fn draw(&mut self, deck: &mut Deck) -> bool {
-        deck.draw().map(|card| self.cards.push_back(card)).is_some()
+    /// The cards in this hand, from left to right.
+    pub(super) fn cards(&self) -> impl DoubleEndedIterator<Item = &Card> + ExactSizeIterator {
+        self.cards[..self.len]
+            .iter()
+            .map(|card| card.as_ref().unwrap())
     }
 
-    pub(super) fn clue(&mut self, player: usize, clue: Clue) -> Result<usize, ClueError> {
-        let matches = self
-            .cards
-            .iter()
-            .filter(|card| match clue {
-                Clue::Color(ref c) => c == &card.color,
-                Clue::Number(ref n) => n == &card.number,
-            })
-            .count();
+    pub(super) fn get(&self, card: usize) -> Option<&Card> {
+        self.cards[..self.len].get(card).and_then(Option::as_ref)
+    }
 
-        if matches == 0 {
-            return Err(ClueError::NoMatchingCards);
-        }
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
 
-        for card in &mut self.cards {
-            card.clues.push((player, clue));
-        }
+    /// Draw a card from `deck` into the right-most slot, and return it if there was one.
+    ///
+    /// `now` is the number of actions taken in the game so far.
+    pub(super) fn draw(&mut self, deck: &mut Deck, now: usize) -> Option<Card> {
+        let mut card = deck.draw()?;
+        card.drawn = now;
+        self.cards[self.len] = Some(card);
+        self.len += 1;
+        Some(card)
+    }
 
-        Ok(matches)
+    /// Give `clue` to every card in this hand, and return the slots it touched.
+    pub(super) fn clue(&mut self, clue: Clue) -> Slots {
+        let mut touched = Slots::default();
+        for (slot, card) in self.cards[..self.len].iter_mut().flatten().enumerate() {
+            let touches = clue.touches(card.color, card.number);
+            card.knowledge.clued(clue, touches);
+            if touches {
+                touched.insert(slot);
+            }
+        }
+        touched
     }
 
     pub(super) fn remove(&mut self, card: usize) -> Option<Card> {
-        if card > self.cards.len() {
+        if card >= self.len {
             return None;
         }
 
-        let mut after = self.cards.split_off(card);
-        let card = after.pop_front();
-        self.cards.append(&mut after);
-        card
+        let removed = self.cards[card].take();
+        self.cards[card..self.len].rotate_left(1);
+        self.len -= 1;
+        removed
     }
 }
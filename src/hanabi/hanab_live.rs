@@ -0,0 +1,235 @@
+//! Converting games to and from the JSON format used by [hanab.live](https://hanab.live).
+//!
+//! hanab.live's replay tools are far better than anything we have, so it's handy to be able to
+//! look at our games there. Its "No Variant" has the same rules as our game, but uses purple where
+//! we use white, so our white cards become purple ones.
+//!
+//! In the format, cards are referred to by their position in the deck (their "order"), which is
+//! exactly our card ids.
+
+use super::record::Record;
+use super::{Action, ActionError, Clue, Color, Game, Number, NUMBERS};
+use serde::{Deserialize, Serialize};
+
+/// A game in hanab.live's JSON format.
+///
+/// When only a deck is given, `players` and `actions` may be left out.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameJson {
+    /// The players, in turn order.
+    #[serde(default)]
+    pub players: Vec<String>,
+    /// The whole deck, in the order the cards were drawn.
+    pub deck: Vec<CardJson>,
+    /// Every action taken, in order.
+    #[serde(default)]
+    pub actions: Vec<ActionJson>,
+    /// The rules the game is played with.
+    #[serde(default)]
+    pub options: OptionsJson,
+}
+
+/// The name of the only hanab.live variant we play.
+pub const NO_VARIANT: &str = "No Variant";
+
+/// The options for a game in hanab.live's JSON format.
+///
+/// hanab.live has many more options, but only the variant changes the cards or the rules in a way
+/// we care about.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptionsJson {
+    pub variant: String,
+}
+
+impl Default for OptionsJson {
+    fn default() -> Self {
+        OptionsJson {
+            variant: NO_VARIANT.to_string(),
+        }
+    }
+}
+
+/// A card in hanab.live's JSON format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardJson {
+    pub suit_index: usize,
+    pub rank: usize,
+}
+
+/// An action in hanab.live's JSON format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionJson {
+    /// What kind of action this is; one of the `ACTION_*` constants.
+    #[serde(rename = "type")]
+    pub kind: u8,
+    /// The card played or discarded, or the player clued.
+    pub target: usize,
+    /// The suit or rank of a clue.
+    #[serde(default)]
+    pub value: usize,
+}
+
+pub const ACTION_PLAY: u8 = 0;
+pub const ACTION_DISCARD: u8 = 1;
+pub const ACTION_COLOR_CLUE: u8 = 2;
+pub const ACTION_RANK_CLUE: u8 = 3;
+pub const ACTION_GAME_OVER: u8 = 4;
+
+/// The reasons a hanab.live game can't be imported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportError {
+    /// The game is played with a variant other than "No Variant".
+    Variant,
+    /// A card's suit isn't one we play with.
+    UnknownSuit(usize),
+    /// A card's or a clue's rank isn't between 1 and 5.
+    UnknownRank(usize),
+    /// The deck isn't exactly the cards of a full deck.
+    NotAFullDeck,
+    /// Games must have between 2 and 5 players.
+    Players(usize),
+    /// The action with this index is of a kind we don't know about.
+    UnknownAction(usize),
+    /// The action with this index plays or discards a card that isn't in the player's hand.
+    CardNotInHand(usize),
+    /// The action with this index isn't legal.
+    IllegalAction(usize, ActionError),
+}
+
+/// hanab.live's suits, in the order it indexes them.
+const SUITS: [Color; 5] = [
+    Color::Red,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::White,
+];
+
+fn suit_index(color: Color) -> usize {
+    SUITS.iter().position(|&c| c == color).unwrap()
+}
+
+fn rank(rank: usize) -> Result<Number, ImportError> {
+    NUMBERS
+        .get(rank.wrapping_sub(1))
+        .copied()
+        .ok_or(ImportError::UnknownRank(rank))
+}
+
+/// The deck in `json`, in the order the cards are drawn.
+pub fn import_deck(json: &GameJson) -> Result<Vec<(Color, Number)>, ImportError> {
+    if json.options.variant != NO_VARIANT {
+        return Err(ImportError::Variant);
+    }
+    json.deck
+        .iter()
+        .map(|card| {
+            let color = *SUITS
+                .get(card.suit_index)
+                .ok_or(ImportError::UnknownSuit(card.suit_index))?;
+            Ok((color, rank(card.rank)?))
+        })
+        .collect()
+}
+
+/// Convert a game in hanab.live's format to a record of it.
+pub fn import(json: &GameJson) -> Result<Record, ImportError> {
+    let deck = import_deck(json)?;
+    if !(2..=5).contains(&json.players.len()) {
+        return Err(ImportError::Players(json.players.len()));
+    }
+    let mut game = Game::with_deck_order(json.players.iter().map(|p| &**p), deck.iter().copied())
+        .ok_or(ImportError::NotAFullDeck)?;
+
+    for (turn, action) in json.actions.iter().enumerate() {
+        let me = turn % json.players.len();
+        let slot = || {
+            let view = game.observe(me);
+            (0..view.hand_size(me))
+                .find(|&slot| view.card_id(me, slot) == Some(action.target))
+                .ok_or(ImportError::CardNotInHand(turn))
+        };
+        let action = match action.kind {
+            ACTION_PLAY => Action::Play(slot()?),
+            ACTION_DISCARD => Action::Discard(slot()?),
+            ACTION_COLOR_CLUE => Action::Clue {
+                player: action.target,
+                clue: Clue::Color(
+                    *SUITS
+                        .get(action.value)
+                        .ok_or(ImportError::UnknownSuit(action.value))?,
+                ),
+            },
+            ACTION_RANK_CLUE => Action::Clue {
+                player: action.target,
+                clue: Clue::Number(rank(action.value)?),
+            },
+            ACTION_GAME_OVER => break,
+            _ => return Err(ImportError::UnknownAction(turn)),
+        };
+        game.apply(action)
+            .map_err(|e| ImportError::IllegalAction(turn, e))?;
+    }
+
+    Ok(game.record().expect("game was dealt from a deck"))
+}
+
+/// Convert the game in `record` to hanab.live's format.
+///
+/// Fails if one of the actions in the record was not legal.
+pub fn export(record: &Record) -> Result<GameJson, ActionError> {
+    let mut game = record.replay(0)?;
+    let mut actions = Vec::with_capacity(record.actions.len());
+    for (turn, &action) in record.actions.iter().enumerate() {
+        let me = turn % record.players.len();
+        let card = |slot| {
+            game.observe(me)
+                .card_id(me, slot)
+                .ok_or(ActionError::NoSuchCard)
+        };
+        actions.push(match action {
+            Action::Play(slot) => ActionJson {
+                kind: ACTION_PLAY,
+                target: card(slot)?,
+                value: 0,
+            },
+            Action::Discard(slot) => ActionJson {
+                kind: ACTION_DISCARD,
+                target: card(slot)?,
+                value: 0,
+            },
+            Action::Clue {
+                player,
+                clue: Clue::Color(color),
+            } => ActionJson {
+                kind: ACTION_COLOR_CLUE,
+                target: player,
+                value: suit_index(color),
+            },
+            Action::Clue {
+                player,
+                clue: Clue::Number(number),
+            } => ActionJson {
+                kind: ACTION_RANK_CLUE,
+                target: player,
+                value: number.as_usize(),
+            },
+        });
+        game.apply(action)?;
+    }
+
+    Ok(GameJson {
+        players: record.players.clone(),
+        deck: record
+            .deck
+            .iter()
+            .map(|&(color, number)| CardJson {
+                suit_index: suit_index(color),
+                rank: number.as_usize(),
+            })
+            .collect(),
+        actions,
+        options: OptionsJson::default(),
+    })
+}
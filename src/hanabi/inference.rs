@@ -0,0 +1,101 @@
+//! Working out what a player's own cards are likely to be.
+//!
+//! Beyond what clues say about a card, a player can rule out identities they can see elsewhere: in
+//! the other players' hands, in the discard pile, or on the played stacks. Good players do this in
+//! their heads all the time; the functions here do it for them.
+
+use super::strategy::Observation;
+use super::{Color, Number, COLOR_ORDER, NUMBERS};
+
+/// The number of copies of each card identity that the observer cannot see.
+///
+/// These cards must be either in the deck or in the observer's own hand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unseen([[u8; 5]; 5]);
+
+impl Unseen {
+    /// Count the cards that `view`'s observer cannot see.
+    pub fn new(view: &Observation<'_>) -> Self {
+        let mut counts = [[0u8; 5]; 5];
+        for color in COLOR_ORDER {
+            for number in NUMBERS {
+                counts[color as usize][number.as_usize() - 1] = number.copies() as u8;
+            }
+        }
+
+        let mut seen = |(color, number): (Color, Number)| {
+            counts[color as usize][number.as_usize() - 1] -= 1;
+        };
+        for player in (0..view.players()).filter(|&p| p != view.me()) {
+            (0..view.hand_size(player))
+                .filter_map(|slot| view.card(player, slot))
+                .for_each(&mut seen);
+        }
+        view.discards().for_each(&mut seen);
+        for color in COLOR_ORDER {
+            if let Some(top) = view.played(color) {
+                NUMBERS[..top.as_usize()]
+                    .iter()
+                    .for_each(|&number| seen((color, number)));
+            }
+        }
+
+        Unseen(counts)
+    }
+
+    /// The number of unseen copies of the given card.
+    pub fn get(&self, color: Color, number: Number) -> usize {
+        usize::from(self.0[color as usize][number.as_usize() - 1])
+    }
+}
+
+/// How likely a card is to have each possible identity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Distribution([[f64; 5]; 5]);
+
+impl Distribution {
+    /// The probability that the card is the given color and number.
+    pub fn get(&self, color: Color, number: Number) -> f64 {
+        self.0[color as usize][number.as_usize() - 1]
+    }
+
+    /// Every identity the card may have, from most to least likely.
+    pub fn likeliest(&self) -> Vec<((Color, Number), f64)> {
+        let mut all: Vec<_> = COLOR_ORDER
+            .iter()
+            .flat_map(|&c| NUMBERS.iter().map(move |&n| (c, n)))
+            .map(|(c, n)| ((c, n), self.get(c, n)))
+            .filter(|&(_, p)| p > 0.0)
+            .collect();
+        all.sort_by(|a, b| b.1.total_cmp(&a.1));
+        all
+    }
+}
+
+/// For each card in the observer's hand, how likely it is to be each identity.
+///
+/// Each card is considered on its own: an identity's weight is the number of unseen copies of it,
+/// or zero if the clues the card has received rule it out. This ignores that two cards in the same
+/// hand can't both be the last copy of a card, but is otherwise what a careful player would work
+/// out.
+pub fn hand(view: &Observation<'_>) -> Vec<Distribution> {
+    let unseen = Unseen::new(view);
+    (0..view.hand_size(view.me()))
+        .map(|slot| {
+            let knowledge = view.knowledge(view.me(), slot).unwrap();
+            let mut weights = [[0.0; 5]; 5];
+            for color in knowledge.colors() {
+                for number in knowledge.numbers() {
+                    weights[color as usize][number.as_usize() - 1] =
+                        unseen.get(color, number) as f64;
+                }
+            }
+
+            let total: f64 = weights.iter().flatten().sum();
+            if total > 0.0 {
+                weights.iter_mut().flatten().for_each(|w| *w /= total);
+            }
+            Distribution(weights)
+        })
+        .collect()
+}
@@ -1,13 +1,26 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::time::{Duration, SystemTime, SystemTimeError};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
 
 mod components;
+pub use self::components::{Action, ActionError, Clue, Color, Knowledge, Number};
 use self::components::{Card, Deck, Hand};
-pub(crate) use self::components::{Clue, Color, Number};
-pub(crate) use self::components::{ClueError, DiscardError, PlayError};
+pub use self::components::{Effect, GameOver, Slots, TurnOutcome};
+
+pub mod analysis;
+pub mod builder;
+pub mod hanab_live;
+pub mod inference;
+pub mod puzzle;
+pub mod record;
+pub mod solver;
+pub mod strategy;
+use self::strategy::{Heuristic, Observation, OracleObservation, Reason, Strategy, Suggestion};
 
 /// We want to ensure that we always print colors in the same order.
+///
+/// This is also the order of the colors when they're used as indices (i.e., `color as usize`).
 const COLOR_ORDER: [Color; 5] = [
     Color::Red,
     Color::Green,
@@ -16,12 +29,21 @@ const COLOR_ORDER: [Color; 5] = [
     Color::Yellow,
 ];
 
+/// All the numbers, in ascending order.
+const NUMBERS: [Number; 5] = [
+    Number::One,
+    Number::Two,
+    Number::Three,
+    Number::Four,
+    Number::Five,
+];
+
 /// Pretty-print a duration.
-fn dur(t: Result<Duration, SystemTimeError>) -> String {
-    if t.is_err() {
+fn dur(t: Option<Duration>) -> String {
+    let Some(t) = t else {
         return "a while".to_owned();
-    }
-    let t = t.unwrap().as_secs();
+    };
+    let t = t.as_secs();
 
     if t > 24 * 60 * 60 {
         format!("{} days", t / (24 * 60 * 60))
@@ -34,60 +56,148 @@ fn dur(t: Result<Duration, SystemTimeError>) -> String {
     }
 }
 
-/// Pretty-print and restart last move time.
-fn dur_mod(start: &mut SystemTime) -> String {
-    let t = start.elapsed();
+/// Restart last move time, and return how long it had been since the last restart.
+fn restart(start: &mut SystemTime) -> Option<Duration> {
+    let t = start.elapsed().ok();
     *start = SystemTime::now();
-    dur(t)
+    t
 }
 
+/// The last move made in a game.
+///
+/// We only keep the facts about the move here, and leave it to `show_to` to turn it into text, so
+/// that games that nobody is watching (like simulations) don't pay for formatting.
 #[derive(Serialize, Deserialize)]
 struct Move {
-    player: usize,
-    for_public: String,
-    for_others: String,
+    outcome: TurnOutcome,
+    after: Option<Duration>,
 }
 
 impl Move {
-    pub fn new(player: usize, for_public: String, for_others: String) -> Move {
-        Move {
-            player,
-            for_public,
-            for_others,
-        }
+    /// Describe this move as it should be shown to the `player`'th player.
+    ///
+    /// The player who made the move doesn't get to see what card they drew.
+    fn show_to(&self, player: usize, hands: &[Hand]) -> String {
+        self.describe(player, hands, Some(&dur(self.after)))
     }
 
-    pub fn show_to(&self, player: usize) -> &str {
-        if player == self.player {
-            &self.for_public
-        } else {
-            &self.for_others
+    /// Like `show_to`, but only says how long the move took if `after` is given.
+    fn describe(&self, player: usize, hands: &[Hand], after: Option<&str>) -> String {
+        let who = &hands[self.outcome.player].player;
+        let mut did = match self.outcome.effect {
+            Effect::Clued {
+                player: to,
+                clue,
+                touched,
+            } => format!(
+                "<@{}> clued <@{}> that {} {} {}",
+                who,
+                hands[to].player,
+                touched,
+                if touched.len() == 1 { "is" } else { "are" },
+                clue,
+            ),
+            Effect::Played { card: (c, n) } => format!("<@{who}> played a {c} {n}"),
+            Effect::Misplayed { card: (c, n) } => {
+                format!("<@{who}> incorrectly played a {c} {n}")
+            }
+            Effect::Discarded { card: (c, n) } => format!("<@{who}> discarded a {c} {n}"),
+        };
+        if let Some(after) = after {
+            did.push_str(&format!(" after {after}"));
+        }
+        if player != self.outcome.player {
+            if let Some((c, n)) = self.outcome.drew {
+                did.push_str(&format!(", and then drew a {c} {n}"));
+            }
         }
+        did
     }
 }
 
 #[derive(Serialize, Deserialize)]
-pub(crate) struct Game {
+pub struct Game {
     deck: Deck,
     hands: Vec<Hand>,
-    played: HashMap<Color, Number>,
-    discard: HashMap<Color, Vec<Card>>,
-    last_move: Move,
+    /// The top card of each stack, indexed by color.
+    played: [Option<Number>; 5],
+    /// The number of discarded cards of each identity, indexed by color and then number.
+    discard: [[u8; 5]; 5],
+    last_move: Option<Move>,
     last_move_at: SystemTime,
     clues: usize,
     lives: usize,
     turn: usize,
+    /// The hand index of the player who took the first turn.
+    first_turn: usize,
+    /// The number of clues given so far.
+    clues_given: usize,
 
     last_turns: Option<usize>,
     started: SystemTime,
 
-    is_unwinnable: bool,
+    /// The max score the players were last told about.
+    max_score: usize,
+
+    /// The whole deck before any cards were dealt, in the order the cards were drawn.
+    dealt: Vec<(Color, Number)>,
+    /// Every action taken so far, in order.
+    history: Vec<Action>,
+
+    /// Each player's private notes, indexed by card id and then by the note-taker's hand index.
+    notes: BTreeMap<usize, BTreeMap<usize, String>>,
+
+    /// Whether players can see their own cards, which makes the game practice rather than ranked.
+    open_hands: bool,
+
+    /// Clues given before the game was set up with a builder, each with the ids of the cards in
+    /// the hand it was given to.
+    setup_clues: Vec<(Clue, Vec<usize>)>,
 }
 
 impl Game {
     /// Start a new game for the given players with a freshly shuffled deck.
-    pub(crate) fn new<'a>(players: impl IntoIterator<Item = &'a str>) -> Self {
-        let mut deck = Deck::default();
+    pub fn new<'a>(players: impl IntoIterator<Item = &'a str>) -> Self {
+        Self::with_deck(players, Deck::default())
+    }
+
+    /// Start a new game for the given players with a deck shuffled by `rng`.
+    ///
+    /// Two games started with identically seeded `rng`s will be dealt the same cards.
+    pub fn with_rng<'a>(
+        players: impl IntoIterator<Item = &'a str>,
+        rng: &mut (impl rand::Rng + ?Sized),
+    ) -> Self {
+        Self::with_deck(players, Deck::shuffled(rng))
+    }
+
+    /// Start a new game for the given players that deals the cards in `deck` in order.
+    ///
+    /// Returns `None` if there aren't between 2 and 5 players, or if `deck` isn't exactly the cards
+    /// of a full deck.
+    pub fn with_deck_order<'a>(
+        players: impl IntoIterator<Item = &'a str>,
+        deck: impl IntoIterator<Item = (Color, Number)>,
+    ) -> Option<Self> {
+        let players: Vec<_> = players.into_iter().collect();
+        if !(2..=5).contains(&players.len()) {
+            return None;
+        }
+        let deck: Vec<_> = deck.into_iter().collect();
+        let mut counts = [[0; 5]; 5];
+        for &(color, number) in &deck {
+            counts[color as usize][number.as_usize() - 1] += 1;
+        }
+        let full = COLOR_ORDER.iter().all(|&color| {
+            NUMBERS
+                .iter()
+                .all(|&number| counts[color as usize][number.as_usize() - 1] == number.copies())
+        });
+        full.then(|| Self::with_deck(players, Deck::from_order(deck)))
+    }
+
+    fn with_deck<'a>(players: impl IntoIterator<Item = &'a str>, mut deck: Deck) -> Self {
+        let dealt = deck.cards().map(|card| (card.color, card.number)).collect();
         let mut hands: Vec<_> = players.into_iter().map(Hand::new).collect();
         let cards = match hands.len() {
             0 | 1 => unreachable!(),
@@ -98,8 +208,8 @@ impl Game {
 
         for hand in &mut hands {
             for _ in 0..cards {
-                let drew = hand.draw(&mut deck);
-                assert!(drew);
+                let drew = hand.draw(&mut deck, 0);
+                assert!(drew.is_some());
             }
         }
 
@@ -108,26 +218,57 @@ impl Game {
             deck,
             played: Default::default(),
             discard: Default::default(),
-            last_move: Move::new(0, "".to_owned(), "".to_owned()),
+            last_move: None,
             last_move_at: SystemTime::now(),
             clues: 8,
             lives: 3,
             turn: 0,
+            first_turn: 0,
+            clues_given: 0,
 
             last_turns: None,
             started: SystemTime::now(),
 
-            is_unwinnable: false,
+            max_score: 25,
+            dealt,
+            history: Vec::new(),
+            notes: BTreeMap::new(),
+            open_hands: false,
+            setup_clues: Vec::new(),
         }
     }
 
+    /// Let every player see their own cards, for teaching people the rules.
+    ///
+    /// Games with open hands are not ranked.
+    pub fn with_open_hands(mut self) -> Self {
+        self.open_hands = true;
+        self
+    }
+
+    /// Returns true if the game is played by the normal rules, and so counts towards rankings.
+    pub fn is_ranked(&self) -> bool {
+        !self.open_hands
+    }
+
     /// Current total score of this game.
-    pub(crate) fn score(&self) -> usize {
-        self.played.values().map(|num| num.as_usize()).sum()
+    pub fn score(&self) -> usize {
+        self.played.iter().flatten().map(|num| num.as_usize()).sum()
+    }
+
+    /// The number of lives the team has left.
+    pub fn lives(&self) -> usize {
+        self.lives
+    }
+
+    /// Returns true if the game has ended, either through a loss of all lives, through the last
+    /// round completing, or because all the stacks are complete.
+    pub fn is_over(&self) -> bool {
+        self.lives == 0 || self.last_turns == Some(self.hands.len()) || self.score() == 25
     }
 
     /// Enumerate the usernames of the players in this game.
-    pub(crate) fn players<'a>(&'a self) -> impl Iterator<Item = &'a str> + use<'a> {
+    pub fn players<'a>(&'a self) -> impl Iterator<Item = &'a str> + use<'a> {
         self.hands.iter().map(|h| &*h.player)
     }
 
@@ -136,184 +277,222 @@ impl Game {
         &self.hands[self.turn].player
     }
 
-    /// Have the current player give `clue` to `to`.
-    pub(crate) fn clue(&mut self, to: &str, clue: Clue) -> Result<usize, ClueError> {
-        if self.clues == 0 {
-            return Err(ClueError::NotEnoughClues);
-        }
+    /// The number of cards in the given player's hand.
+    pub(crate) fn hand_size(&self, player: &str) -> usize {
+        self.player_index(player)
+            .map_or(0, |player| self.hands[player].len())
+    }
 
-        // the clone here is unfortunate, but otherwise it's a double-borrow out of self.hands
-        let player = self.hands[self.turn].player.clone();
-        if self.hands[self.turn].player == to {
-            return Err(ClueError::NoSuchPlayer);
-        }
+    /// Get the position in turn order of the player with the given username.
+    pub(crate) fn player_index(&self, player: &str) -> Option<usize> {
+        self.hands.iter().position(|hand| hand.player == player)
+    }
 
-        let hands = self.hands.len();
-        let hand = if let Some(h) = self.hands.iter_mut().find(|hand| hand.player == to) {
-            h
-        } else {
-            return Err(ClueError::NoSuchPlayer);
-        };
+    /// Enumerate every action the current player could legally take.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        if self.is_over() {
+            return Vec::new();
+        }
 
-        match hand.clue(self.turn, clue) {
-            Ok(num) => {
-                let did = format!(
-                    "<@{}> clued <@{}> that {} {} {} after {}",
-                    player,
-                    to,
-                    num,
-                    if num == 1 { "card is" } else { "cards are" },
-                    clue,
-                    dur_mod(&mut self.last_move_at),
-                );
-                self.last_move = Move::new(self.turn, did.clone(), did);
-                self.clues -= 1;
-                self.turn = (self.turn + 1) % hands;
-                if let Some(ref mut last_turns) = self.last_turns {
-                    *last_turns += 1;
-                    if *last_turns == hands {
-                        return Err(ClueError::GameOver);
-                    }
+        let cards = self.hands[self.turn].len();
+        let mut actions: Vec<_> = (0..cards).map(Action::Play).collect();
+        if self.clues < 8 {
+            actions.extend((0..cards).map(Action::Discard));
+        }
+        if self.clues > 0 {
+            for (player, hand) in self.hands.iter().enumerate() {
+                if player == self.turn {
+                    continue;
                 }
-                Ok(num)
+                let colors = COLOR_ORDER.iter().copied().map(Clue::Color);
+                let numbers = NUMBERS.iter().copied().map(Clue::Number);
+                actions.extend(
+                    colors
+                        .chain(numbers)
+                        .filter(|clue| hand.cards().any(|c| clue.touches(c.color, c.number)))
+                        .map(|clue| Action::Clue { player, clue }),
+                );
             }
-            e => e,
         }
+        actions
     }
 
-    /// Have the current player play the `card`'th card from the left (0-indexed).
-    pub(crate) fn play(&mut self, card: usize) -> Result<(), PlayError> {
-        let hands = self.hands.len();
-        let hand = self.turn;
-        if let Some(card) = self.hands.get_mut(hand).unwrap().remove(card) {
-            self.hands.get_mut(hand).unwrap().draw(&mut self.deck);
+    /// Check whether the current player could take `action`, without taking it.
+    pub fn validate(&self, action: &Action) -> Result<(), ActionError> {
+        if self.is_over() {
+            return Err(ActionError::GameOver);
+        }
 
-            use std::collections::hash_map::Entry;
-            let success = match self.played.entry(card.color) {
-                Entry::Vacant(e) => {
-                    if card.number == Number::One {
-                        e.insert(Number::One);
-                        true
-                    } else {
-                        false
-                    }
+        match *action {
+            Action::Clue { player, clue } => {
+                if self.clues == 0 {
+                    return Err(ActionError::NotEnoughClues);
                 }
-                Entry::Occupied(mut e) => {
-                    if card.number == *e.get() + 1 {
-                        e.insert(card.number);
-                        if card.number == Number::Five {
-                            // completed a stack!
-                            // get a clue.
-                            if self.clues < 8 {
-                                self.clues += 1;
-                            }
-                        }
-                        true
-                    } else {
-                        false
-                    }
+                if player == self.turn {
+                    return Err(ActionError::ClueToSelf);
                 }
-            };
-
-            let drew = if self.last_turns.is_none() {
-                format!(
-                    ", and then drew a {}",
-                    self.hands[hand].cards.back().unwrap()
-                )
-            } else {
-                "".to_owned()
-            };
-
-            if !success {
-                self.lives -= 1;
-                let did = format!(
-                    "<@{}> incorrectly played a {} after {}",
-                    self.hands[self.turn].player,
-                    card,
-                    dur_mod(&mut self.last_move_at),
-                );
-                self.last_move = Move::new(self.turn, did.clone(), format!("{did}{drew}"));
-
-                self.discarded(card);
-
-                if self.lives == 0 {
-                    return Err(PlayError::GameOver);
+                let hand = self.hands.get(player).ok_or(ActionError::NoSuchPlayer)?;
+                if !hand.cards().any(|c| clue.touches(c.color, c.number)) {
+                    return Err(ActionError::NoMatchingCards);
                 }
-            } else {
-                let did = format!(
-                    "<@{}> played a {} after {}",
-                    self.hands[self.turn].player,
-                    card,
-                    dur_mod(&mut self.last_move_at),
-                );
-                self.last_move = Move::new(self.turn, did.clone(), format!("{did}{drew}"));
             }
-
-            self.turn = (self.turn + 1) % hands;
-            if let Some(ref mut last_turns) = self.last_turns {
-                *last_turns += 1;
-                if *last_turns == hands {
-                    return Err(PlayError::GameOver);
+            Action::Play(card) => {
+                if card >= self.hands[self.turn].len() {
+                    return Err(ActionError::NoSuchCard);
+                }
+            }
+            Action::Discard(card) => {
+                if self.clues == 8 {
+                    return Err(ActionError::MaxClues);
+                }
+                if card >= self.hands[self.turn].len() {
+                    return Err(ActionError::NoSuchCard);
                 }
-            } else if self.deck.is_empty() {
-                self.last_turns = Some(0);
             }
-            Ok(())
-        } else {
-            Err(PlayError::NoSuchCard)
         }
+        Ok(())
     }
 
-    /// Have the current player discard the `card`'th card from the left (0-indexed).
-    pub(crate) fn discard(&mut self, card: usize) -> Result<(), DiscardError> {
-        if self.clues == 8 {
-            return Err(DiscardError::MaxClues);
+    /// Get the game as seen by the `player`'th player (in turn order).
+    pub fn observe(&self, player: usize) -> Observation<'_> {
+        Observation::new(self, player)
+    }
+
+    /// Get the game as seen by the `player`'th player, including their own cards.
+    ///
+    /// This is only meant for oracles and for checking what players infer against the truth.
+    pub fn observe_all(&self, player: usize) -> OracleObservation<'_> {
+        OracleObservation::new(self, player)
+    }
+
+    /// Let `strategy` decide on and make the current player's move.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the strategy chooses a move that is not legal in the current game state.
+    pub fn take_turn(&mut self, strategy: &mut (impl Strategy + ?Sized)) -> TurnOutcome {
+        let action = strategy.decide(&self.observe(self.turn));
+        match self.apply(action) {
+            Ok(outcome) => outcome,
+            Err(e) => panic!("strategy chose illegal action {action:?}: {e:?}"),
         }
+    }
 
-        let hands = self.hands.len();
-        let hand = self.turn;
-        if let Some(card) = self.hands.get_mut(hand).unwrap().remove(card) {
-            self.hands.get_mut(hand).unwrap().draw(&mut self.deck);
+    /// Play the game to completion, with the `i`th player's moves chosen by `strategies[i]`.
+    ///
+    /// Returns the final score.
+    pub fn play_out(&mut self, strategies: &mut [Box<dyn Strategy>]) -> usize {
+        assert_eq!(strategies.len(), self.hands.len());
+        while !self.is_over() {
+            let turn = self.turn;
+            self.take_turn(&mut *strategies[turn]);
+        }
+        self.score()
+    }
 
-            let drew = if self.last_turns.is_none() {
-                format!(
-                    ", and then drew a {}",
-                    self.hands[hand].cards.back().unwrap()
-                )
-            } else {
-                "".to_owned()
-            };
-
-            let did = format!(
-                "<@{}> discarded a {} after {}",
-                self.hands[self.turn].player,
-                card,
-                dur_mod(&mut self.last_move_at),
-            );
-            self.last_move = Move::new(self.turn, did.clone(), format!("{did}{drew}"));
+    /// Have the current player take `action`.
+    ///
+    /// If the action is not legal, the game is left unchanged.
+    pub fn apply(&mut self, action: Action) -> Result<TurnOutcome, ActionError> {
+        self.validate(&action)?;
+
+        let player = self.turn;
+        let mut drew = None;
+        let mut clue_refunded = false;
+        let effect = match action {
+            Action::Clue { player: to, clue } => {
+                let touched = self.hands[to].clue(clue);
+                self.clues -= 1;
+                self.clues_given += 1;
+                Effect::Clued {
+                    player: to,
+                    clue,
+                    touched,
+                }
+            }
+            Action::Play(card) => {
+                let card = self.hands[player].remove(card).expect("validated");
+                drew = self.hands[player].draw(&mut self.deck, self.history.len());
+
+                if self.is_playable(card.color, card.number) {
+                    self.played[card.color as usize] = Some(card.number);
+                    if card.number == Number::Five {
+                        // completed a stack!
+                        // get a clue.
+                        if self.clues < 8 {
+                            self.clues += 1;
+                            clue_refunded = true;
+                        }
+                    }
+                    Effect::Played {
+                        card: (card.color, card.number),
+                    }
+                } else {
+                    self.lives -= 1;
+                    self.discarded(card);
+                    Effect::Misplayed {
+                        card: (card.color, card.number),
+                    }
+                }
+            }
+            Action::Discard(card) => {
+                let card = self.hands[player].remove(card).expect("validated");
+                drew = self.hands[player].draw(&mut self.deck, self.history.len());
 
-            self.discarded(card);
-            self.clues += 1;
-            self.turn = (self.turn + 1) % hands;
+                self.discarded(card);
+                self.clues += 1;
+                clue_refunded = true;
+                Effect::Discarded {
+                    card: (card.color, card.number),
+                }
+            }
+        };
+
+        let mut last_round_started = false;
+        if self.lives != 0 {
+            self.turn = (self.turn + 1) % self.hands.len();
             if let Some(ref mut last_turns) = self.last_turns {
                 *last_turns += 1;
-                if *last_turns == hands {
-                    return Err(DiscardError::GameOver);
-                }
             } else if self.deck.is_empty() {
                 self.last_turns = Some(0);
+                last_round_started = true;
             }
-            Ok(())
-        } else {
-            Err(DiscardError::NoSuchCard)
         }
+
+        let game_over = if self.lives == 0 {
+            Some(GameOver::OutOfLives)
+        } else if self.score() == 25 {
+            Some(GameOver::Perfect)
+        } else if self.last_turns == Some(self.hands.len()) {
+            Some(GameOver::LastRoundOver)
+        } else {
+            None
+        };
+
+        let outcome = TurnOutcome {
+            player,
+            effect,
+            drew: drew.map(|card| (card.color, card.number)),
+            clue_refunded,
+            last_round_started,
+            game_over,
+        };
+        self.last_move = Some(Move {
+            outcome,
+            after: restart(&mut self.last_move_at),
+        });
+        self.history.push(action);
+        Ok(outcome)
     }
 
     /// Show `user` every other player's hand + what they know.
+    ///
+    /// If `annotate` is set, critical cards and cards that can never be played are marked.
     pub(crate) fn show_hands(
         &self,
         user: &str,
         skip_self: bool,
+        annotate: bool,
         cli: &mut impl super::MessageProxy,
     ) {
         let me = self
@@ -333,48 +512,270 @@ impl Game {
             } else {
                 cli.send(user, &format!("<@{}>", self.hands[hand].player));
             }
-            let (cards, known): (Vec<_>, Vec<_>) = self.hands[hand]
-                .cards
-                .iter()
-                .map(|card| (format!("{card}"), card.known()))
-                .unzip();
-
-            if hand == me {
-                if !skip_self {
-                    cli.send(user, &format!("{} known", known.join("  |  ")));
-                }
+            if hand == me && skip_self {
+                continue;
+            }
+            let (cards, known) = self.hand_rows(hand, me, annotate);
+            if hand == me && !self.open_hands {
+                cli.send(user, &format!("{known} known"));
             } else {
-                cli.send(
-                    user,
-                    &format!(
-                        "{} in hand\n{} known",
-                        cards.join("  |  "),
-                        known.join("  |  ")
-                    ),
-                );
+                cli.send(user, &format!("{cards} in hand\n{known} known"));
             }
         }
     }
 
+    /// The cards in the `hand`th player's hand, and what they know about them, as seen by the
+    /// `viewer`th player.
+    fn hand_rows(&self, hand: usize, viewer: usize, annotate: bool) -> (String, String) {
+        let (cards, known): (Vec<_>, Vec<_>) = self.hands[hand]
+            .cards()
+            .enumerate()
+            .map(|(slot, card)| {
+                let shown = if !annotate {
+                    format!("{card}")
+                } else if self.is_trash(card.color, card.number) {
+                    format!("{card} :wastebasket:")
+                } else if self.is_critical(card.color, card.number) {
+                    format!("{card} :rotating_light:")
+                } else {
+                    format!("{card}")
+                };
+                (shown, self.known_by(hand, slot, viewer))
+            })
+            .unzip();
+        (cards.join("  |  "), known.join("  |  "))
+    }
+
     /// Show `user` the current state of the discard pile.
     pub(crate) fn show_discards(&self, user: &str, cli: &mut impl super::MessageProxy) {
-        if self.discard.is_empty() {
+        if self.discard.iter().flatten().all(|&n| n == 0) {
             cli.send(user, "The discard pile is empty.");
             return;
         }
 
         cli.send(user, "The discard pile contains the following cards:");
-        for color in &COLOR_ORDER {
-            if let Some(cards) = self.discard.get(color) {
+        for &color in &COLOR_ORDER {
+            let counts = &self.discard[color as usize];
+            if counts.iter().any(|&n| n != 0) {
                 let mut out = format!("{color} ");
-                for card in cards {
-                    out.push_str(&format!("{}", card.number));
+                for (&number, &n) in NUMBERS.iter().zip(counts) {
+                    for _ in 0..n {
+                        out.push_str(&format!("{number}"));
+                    }
                 }
                 cli.send(user, &out);
             }
         }
     }
 
+    /// Show `user` every identity the `card`'th card from the left (0-indexed) in their hand could
+    /// still have given the clues they've received.
+    pub(crate) fn show_card(&self, user: &str, card: usize, cli: &mut impl super::MessageProxy) {
+        let me = self.player_index(user).unwrap();
+        let Some(k) = self.hands[me].get(card).map(|c| c.knowledge) else {
+            cli.send(
+                user,
+                "The card you specified is not in your hand. \
+                 Remember that card indexing starts at 1.",
+            );
+            return;
+        };
+
+        if let (Some(c), Some(n)) = (k.color(), k.number()) {
+            cli.send(user, &format!("Your card {} must be a {c} {n}.", card + 1));
+            return;
+        }
+
+        cli.send(
+            user,
+            &format!(
+                "From the clues you've been given, your card {} could be any of:",
+                card + 1
+            ),
+        );
+        for color in k.colors() {
+            let numbers: Vec<_> = k.numbers().map(|n| format!("{color} {n}")).collect();
+            cli.send(user, &numbers.join("  |  "));
+        }
+    }
+
+    /// Show `user` how many copies of each card they cannot see anywhere, and so must be either in
+    /// the deck or in their own hand.
+    pub(crate) fn show_remaining(&self, user: &str, cli: &mut impl super::MessageProxy) {
+        let me = self.player_index(user).unwrap();
+        let unseen = inference::Unseen::new(&self.observe(me));
+
+        cli.send(
+            user,
+            &format!(
+                "These {} cards are in the deck or in your hand:",
+                self.deck.len() + self.hands[me].len()
+            ),
+        );
+        for color in COLOR_ORDER {
+            let mut out = format!("{color} ");
+            for number in NUMBERS {
+                for _ in 0..unseen.get(color, number) {
+                    out.push_str(&format!("{number}"));
+                }
+            }
+            if NUMBERS.iter().any(|&n| unseen.get(color, n) != 0) {
+                cli.send(user, &out);
+            }
+        }
+    }
+
+    /// Show `user` which clues have touched or ruled out each card in every player's hand, and who
+    /// gave them.
+    pub(crate) fn show_clues(&self, user: &str, cli: &mut impl super::MessageProxy) {
+        let me = self.player_index(user).unwrap();
+
+        for i in 0..self.hands.len() {
+            let hand = (me + i) % self.hands.len();
+            if hand == me {
+                cli.send(user, "Clues given to your cards:");
+            } else {
+                cli.send(
+                    user,
+                    &format!("Clues given to <@{}>'s cards:", self.hands[hand].player),
+                );
+            }
+
+            for (slot, card) in self.hands[hand].cards().enumerate() {
+                let before = self
+                    .setup_clues
+                    .iter()
+                    .filter(|(_, ids)| ids.contains(&card.id))
+                    .map(|&(clue, _)| {
+                        if clue.touches(card.color, card.number) {
+                            format!("{clue} before play began")
+                        } else {
+                            format!("not {clue} before play began")
+                        }
+                    });
+                let during =
+                    self.history
+                        .iter()
+                        .enumerate()
+                        .skip(card.drawn)
+                        .filter_map(|(i, action)| match *action {
+                            Action::Clue { player, clue } if player == hand => {
+                                let giver = (self.first_turn + i) % self.hands.len();
+                                let giver = &self.hands[giver].player;
+                                if clue.touches(card.color, card.number) {
+                                    Some(format!("{clue} from <@{giver}>"))
+                                } else {
+                                    Some(format!("not {clue} from <@{giver}>"))
+                                }
+                            }
+                            _ => None,
+                        });
+                let clues: Vec<_> = before.chain(during).collect();
+                if clues.is_empty() {
+                    cli.send(user, &format!("{}: no clues", slot + 1));
+                } else {
+                    cli.send(user, &format!("{}: {}", slot + 1, clues.join(", ")));
+                }
+            }
+        }
+    }
+
+    /// Show `user` how likely each card in their hand is to be each identity, taking into account
+    /// both their clues and the cards they can see elsewhere.
+    pub(crate) fn show_think(&self, user: &str, cli: &mut impl super::MessageProxy) {
+        let me = self.player_index(user).unwrap();
+        let hand = inference::hand(&self.observe(me));
+
+        cli.send(
+            user,
+            "Given your clues and every card you can see, your hand is most likely:",
+        );
+        for (slot, dist) in hand.iter().enumerate() {
+            let likeliest = dist.likeliest();
+            let mut shown: Vec<_> = likeliest
+                .iter()
+                .take(3)
+                .map(|&((c, n), p)| format!("{c} {n} {:.0}%", 100.0 * p))
+                .collect();
+            if likeliest.len() > 3 {
+                let rest: f64 = likeliest[3..].iter().map(|&(_, p)| p).sum();
+                shown.push(format!("something else {:.0}%", 100.0 * rest));
+            }
+            cli.send(user, &format!("{}: {}", slot + 1, shown.join(", ")));
+        }
+    }
+
+    /// Show `user` the move the built-in [`Heuristic`] strategy would make in their place, and why.
+    ///
+    /// Only what `user` is allowed to know goes into the suggestion.
+    pub(crate) fn show_suggestion(&self, user: &str, cli: &mut impl super::MessageProxy) {
+        let me = self.player_index(user).unwrap();
+        let suggestions = Heuristic::suggestions(&self.observe(me));
+        let Some((best, rest)) = suggestions.split_first() else {
+            return;
+        };
+
+        cli.send(
+            user,
+            &format!(
+                "I'd {}, since {}.",
+                self.describe_action(&best.action),
+                self.explain(best)
+            ),
+        );
+        let others: Vec<_> = rest
+            .iter()
+            .take(2)
+            .map(|s| format!("{} ({})", self.describe_action(&s.action), self.explain(s)))
+            .collect();
+        if !others.is_empty() {
+            cli.send(
+                user,
+                &format!("Next best would be to {}.", others.join(", or to ")),
+            );
+        }
+    }
+
+    /// Describe `action` as an instruction to the current player.
+    fn describe_action(&self, action: &Action) -> String {
+        match *action {
+            Action::Play(slot) => format!("play slot {}", slot + 1),
+            Action::Discard(slot) => format!("discard slot {}", slot + 1),
+            Action::Clue { player, clue } => {
+                format!("clue <@{}> {clue}", self.hands[player].player)
+            }
+        }
+    }
+
+    /// Explain why `suggestion` is worth considering.
+    fn explain(&self, suggestion: &Suggestion) -> String {
+        let whose = match suggestion.action {
+            Action::Clue { player, .. } => format!("<@{}>", self.hands[player].player),
+            _ => String::from("you"),
+        };
+        match suggestion.reason {
+            Reason::MayBePlayable(p) if p > 0.999 => String::from("it's certainly playable"),
+            Reason::MayBePlayable(p) => {
+                format!("there's a {:.0}% chance it's playable", 100.0 * p)
+            }
+            Reason::Useless => String::from("it can never be played"),
+            Reason::MayBeNeeded(p) => {
+                format!("there's a {:.0}% chance it's still needed", 100.0 * p)
+            }
+            Reason::Playable(ref cards) => {
+                let cards: Vec<_> = cards.iter().map(|(c, n)| format!("{c} {n}")).collect();
+                format!(
+                    "it tells {whose} about {} they can play",
+                    cards.join(" and ")
+                )
+            }
+            Reason::Saves((c, n)) => {
+                format!("it saves {whose}'s {c} {n}, the last copy left, from being discarded")
+            }
+            Reason::Stall => String::from("there's nothing more useful to do"),
+        }
+    }
+
     /// Show `user` the current state of the deck.
     pub(crate) fn show_deck(&self, user: &str, cli: &mut impl super::MessageProxy) {
         if self.deck.is_empty() {
@@ -417,41 +818,96 @@ impl Game {
         }
     }
 
-    pub fn became_unwinnable(&mut self) -> bool {
-        if self.is_unwinnable {
-            return false;
+    /// The highest score the team can still achieve.
+    ///
+    /// Each stack can only grow until it reaches a card whose every copy has been discarded, and
+    /// the score can't grow by more than the number of cards that can still be played.
+    pub fn max_score(&self) -> usize {
+        if self.is_over() {
+            return self.score();
         }
+        self.stacks_max_score()
+            .min(self.score() + self.plays_left())
+    }
 
-        // look through the discard pile, and see if all the copies of a given number for any color
-        // has been discarded. if so, the game is no longer winnable.
-        for cards in self.discard.values() {
-            let mut number = cards[0].number;
-            let mut n = 0;
-            for card in cards {
-                if card.number == number {
-                    n += 1;
-                } else {
-                    number = card.number;
-                    n = 1;
-                }
-
-                let total = match number {
-                    Number::One => 3,
-                    Number::Five => 1,
-                    _ => 2,
-                };
-                if n == total {
-                    self.is_unwinnable = true;
-                    return true;
+    /// The highest score the stacks can reach, given which cards have been discarded.
+    fn stacks_max_score(&self) -> usize {
+        let mut max = 0;
+        for color in COLOR_ORDER {
+            let mut top = self.played[color as usize].map_or(0, |n| n.as_usize());
+            while top < 5 {
+                let next = NUMBERS[top];
+                if usize::from(self.discard[color as usize][top]) == next.copies() {
+                    break;
                 }
+                top += 1;
             }
+            max += top;
         }
+        max
+    }
 
-        false
+    /// The most cards that can still be played.
+    ///
+    /// Every play draws a card, so there can be at most one play for every card left in the deck,
+    /// plus one for every turn of the final round that hasn't been taken yet.
+    fn plays_left(&self) -> usize {
+        (self.deck.len() + self.hands.len()).saturating_sub(self.last_turns.unwrap_or(0))
+    }
+
+    /// The number of discards the team can still afford before the max score becomes unreachable.
+    ///
+    /// Every turn either plays a card, or doesn't, and there are only so many turns left: one for
+    /// every card left in the deck, plus a final round for every player. Each turn not spent
+    /// playing a card needed for the max score eats into that margin. Once it is negative, there
+    /// aren't enough turns left to finish every stack that could otherwise still be finished.
+    pub fn pace(&self) -> isize {
+        (self.score() + self.plays_left()) as isize - self.stacks_max_score() as isize
+    }
+
+    /// The number of cards played for every clue given so far, if any clues have been given.
+    pub fn efficiency(&self) -> Option<f64> {
+        if self.clues_given == 0 {
+            None
+        } else {
+            Some(self.score() as f64 / self.clues_given as f64)
+        }
     }
 
-    pub fn last_move(&self) -> &str {
-        &self.last_move.for_public
+    /// If the max score has dropped since the last time this was called, return the new max score
+    /// along with the reason it dropped.
+    pub fn lowered_max_score(&mut self) -> Option<(usize, String)> {
+        let max = self.max_score();
+        if max >= self.max_score {
+            return None;
+        }
+        self.max_score = max;
+
+        let lost = self
+            .last_move
+            .as_ref()
+            .and_then(|m| match m.outcome.effect {
+                Effect::Discarded { card: (c, n) } | Effect::Misplayed { card: (c, n) } => {
+                    Some((c, n))
+                }
+                _ => None,
+            });
+        let reason = match lost {
+            Some((c, n))
+                if usize::from(self.discard[c as usize][n.as_usize() - 1]) == n.copies() =>
+            {
+                format!("lost the last {c} {n}")
+            }
+            _ => "not enough turns left to play every card".to_string(),
+        };
+        Some((max, reason))
+    }
+
+    pub fn last_move(&self) -> String {
+        match self.last_move {
+            Some(ref m) => m.show_to(m.outcome.player, &self.hands),
+            None => String::new(),
+        }
     }
 
     /// Progress the current game following a turn, and return true if the game has ended.
@@ -461,12 +917,18 @@ impl Game {
     ///
     /// This *could* be called automatially internally, but it'd make the return types of all the
     /// action methods somewhat annoying.
-    pub(crate) fn progress_game(&mut self, cli: &mut impl super::MessageProxy) -> bool {
-        if !self.last_move.show_to(0).is_empty() {
+    ///
+    /// `annotate` is called with each player's username to decide whether they want critical and
+    /// trash cards marked when they are shown the other players' hands.
+    pub(crate) fn progress_game(
+        &mut self,
+        cli: &mut impl super::MessageProxy,
+        annotate: impl Fn(&str) -> bool,
+    ) -> bool {
+        if let Some(ref last_move) = self.last_move {
             for (i, hand) in self.hands.iter().enumerate() {
-                let mut m = self
-                    .last_move
-                    .show_to(i)
+                let mut m = last_move
+                    .show_to(i, &self.hands)
                     .replace(&format!("<@{}>", hand.player), "you");
                 if m.starts_with("you") {
                     m = m.replacen("you", "You", 1);
@@ -476,31 +938,21 @@ impl Game {
             }
         }
 
-        let points: usize = self.played.values().map(|num| num.as_usize()).sum();
-        let mut game_over = self.lives == 0;
-        if let Some(last_turns) = self.last_turns {
-            game_over = game_over || last_turns == self.hands.len();
-        }
-        if game_over {
+        let points = self.score();
+        if self.lives == 0 || self.last_turns == Some(self.hands.len()) {
             // the game has ended in a loss :'(
             for hand in &self.hands {
                 cli.send(
                     &hand.player,
                     &format!(
                         "Game over after {}.\n\
-                         You got {}/25 points {}\n\
-                         Your hand at the end was:\n\
-                         {}",
-                        dur(self.started.elapsed()),
+                         You got {}/25 points {}",
+                        dur(self.started.elapsed().ok()),
                         points,
                         self.score_smiley(),
-                        hand.cards
-                            .iter()
-                            .map(|c| format!("{c}"))
-                            .collect::<Vec<_>>()
-                            .join("  |  ")
                     ),
                 );
+                self.show_reveal(&hand.player, cli);
             }
             return true;
         }
@@ -512,36 +964,228 @@ impl Game {
                     &hand.player,
                     &format!(
                         "You won the game with 25/25 points after {} {}",
-                        dur(self.started.elapsed()),
+                        dur(self.started.elapsed().ok()),
                         self.score_smiley()
                     ),
                 );
+                self.show_reveal(&hand.player, cli);
             }
             return true;
         }
 
         // game is not yet over -- let's print the game state
         for i in 0..self.hands.len() {
-            self.print_game_state(i, cli);
+            let annotate = annotate(&self.hands[i].player);
+            self.print_game_state(i, annotate, cli);
         }
 
         false
     }
 
+    /// Describe what is known about the card in `slot` of the `hand`'th player's hand, as shown to
+    /// the `viewer`'th player.
+    ///
+    /// Cards touched by the last clue are pointed out, and the viewer's note on the card is shown.
+    fn known_by(&self, hand: usize, slot: usize, viewer: usize) -> String {
+        let card = self.hands[hand].get(slot).unwrap();
+        let mut known = card.known();
+        if self.just_touched(hand).contains(slot) {
+            known.push_str(" :point_left:");
+        }
+        if let Some(note) = self.notes.get(&card.id).and_then(|n| n.get(&viewer)) {
+            known.push_str(&format!(" :memo: {note}"));
+        }
+        known
+    }
+
+    /// Leave the `user`'s private note on the card in `slot` of the `player`'th player's hand, or
+    /// clear it if `text` is empty.
+    ///
+    /// Returns false if there is no such card.
+    pub(crate) fn note(&mut self, user: &str, player: usize, slot: usize, text: &str) -> bool {
+        let me = self.player_index(user).unwrap();
+        let Some(card) = self.hands[player].get(slot) else {
+            return false;
+        };
+        let notes = self.notes.entry(card.id).or_default();
+        if text.is_empty() {
+            notes.remove(&me);
+        } else {
+            notes.insert(me, text.to_string());
+        }
+        true
+    }
+
+    /// The cards in the `hand`'th player's hand that were touched by a clue on the last turn.
+    fn just_touched(&self, hand: usize) -> Slots {
+        match self.last_move {
+            Some(Move {
+                outcome:
+                    TurnOutcome {
+                        effect:
+                            Effect::Clued {
+                                player, touched, ..
+                            },
+                        ..
+                    },
+                ..
+            }) if player == hand => touched,
+            _ => Slots::default(),
+        }
+    }
+
+    /// Show `user` the full state of the game, with every hand and the discard pile visible, for
+    /// reviewing it after the fact.
+    pub(crate) fn show_review(&self, user: &str, cli: &mut impl super::MessageProxy) {
+        if let Some(ref m) = self.last_move {
+            cli.send(
+                user,
+                &format!(
+                    ":point_right: {}",
+                    m.describe(self.hands.len(), &self.hands, None)
+                ),
+            );
+        }
+        cli.send(
+            user,
+            &format!(
+                "*{}* :information_source: and {} :bomb: remain, with {} cards in the deck.",
+                self.clues,
+                self.lives,
+                self.deck.len()
+            ),
+        );
+        if self.last_turns.is_some() && !self.is_over() {
+            cli.send(
+                user,
+                &format!(
+                    "With every card known, the best score from here is {}.",
+                    solver::best_score(self)
+                ),
+            );
+        }
+        cli.send(
+            user,
+            &format!("Played ({} points):\n{}", self.score(), self.stacks()),
+        );
+        self.show_discards(user, cli);
+        for (i, hand) in self.hands.iter().enumerate() {
+            if i == self.turn && !self.is_over() {
+                cli.send(user, &format!("<@{}> &lt;-- current turn", hand.player));
+            } else {
+                cli.send(user, &format!("<@{}>", hand.player));
+            }
+            let cards: Vec<_> = hand.cards().map(|c| format!("{c}")).collect();
+            let known: Vec<_> = (0..hand.len())
+                .map(|slot| self.known_by(i, slot, i))
+                .collect();
+            cli.send(
+                user,
+                &format!(
+                    "{} in hand\n{} known",
+                    cards.join("  |  "),
+                    known.join("  |  ")
+                ),
+            );
+        }
+    }
+
+    /// The top card of every stack.
+    fn stacks(&self) -> String {
+        let stacks: Vec<_> = COLOR_ORDER
+            .iter()
+            .map(|&color| {
+                if let Some(top) = self.played[color as usize] {
+                    format!("{color} {top}")
+                } else {
+                    format!("{color} :zero:")
+                }
+            })
+            .collect();
+        stacks.join("  |  ")
+    }
+
+    /// Show `user` everything that was hidden during the game: every hand, what was left in the
+    /// deck, and which cards could no longer be played because every copy was discarded.
+    fn show_reveal(&self, user: &str, cli: &mut impl super::MessageProxy) {
+        let mut out = String::from("The hands at the end were:");
+        for hand in &self.hands {
+            let cards: Vec<_> = hand.cards().map(|c| format!("{c}")).collect();
+            out.push_str(&format!("\n<@{}>: {}", hand.player, cards.join("  |  ")));
+        }
+        cli.send(user, &out);
+
+        if self.deck.is_empty() {
+            cli.send(user, "The deck was empty.");
+        } else {
+            let cards: Vec<_> = self.deck.cards().map(|c| format!("{c}")).collect();
+            cli.send(
+                user,
+                &format!(
+                    "The deck still held, in the order they'd be drawn:\n{}",
+                    cards.join("  |  ")
+                ),
+            );
+        }
+
+        let lost: Vec<_> = COLOR_ORDER
+            .iter()
+            .flat_map(|&c| NUMBERS.iter().map(move |&n| (c, n)))
+            .filter(|&(c, n)| {
+                usize::from(self.discard[c as usize][n.as_usize() - 1]) == n.copies()
+                    && self.played[c as usize].is_none_or(|top| top.as_usize() < n.as_usize())
+            })
+            .map(|(c, n)| format!("{c} {n}"))
+            .collect();
+        if !lost.is_empty() {
+            cli.send(
+                user,
+                &format!(
+                    "Every copy of these cards was discarded: {}",
+                    lost.join("  |  ")
+                ),
+            );
+        }
+    }
+
     /// Called whenever a card is discarded.
     fn discarded(&mut self, card: Card) {
-        // insert into sorted discard list for that color
-        let d = self.discard.entry(card.color).or_default();
-        let pos = d
-            .binary_search_by_key(&card.number.as_usize(), |c| c.number.as_usize())
-            .unwrap_or_else(|e| e);
-        d.insert(pos, card);
+        self.discard[card.color as usize][card.number.as_usize() - 1] += 1;
+    }
+
+    /// Returns true if a card with the given identity can never be played, either because it
+    /// already has been, or because every copy of a card below it has been discarded.
+    fn is_trash(&self, color: Color, number: Number) -> bool {
+        let top = self.played[color as usize].map_or(0, |n| n.as_usize());
+        number.as_usize() <= top
+            || NUMBERS[top..number.as_usize() - 1].iter().any(|&below| {
+                usize::from(self.discard[color as usize][below.as_usize() - 1]) == below.copies()
+            })
+    }
+
+    /// Returns true if every other copy of the given card has been discarded, so discarding this
+    /// one would lower the max score.
+    fn is_critical(&self, color: Color, number: Number) -> bool {
+        usize::from(self.discard[color as usize][number.as_usize() - 1]) + 1 == number.copies()
+    }
+
+    /// Returns true if a card with the given identity could be played right now.
+    fn is_playable(&self, color: Color, number: Number) -> bool {
+        match self.played[color as usize] {
+            None => number == Number::One,
+            Some(top) => top != Number::Five && top + 1 == number,
+        }
     }
 
     /// Show the `hand`'th player the current game state.
     ///
     /// Note that the information displayed depends on whether or not it is `hand`'s turn.
-    fn print_game_state(&mut self, hand: usize, cli: &mut impl super::MessageProxy) {
+    fn print_game_state(
+        &mut self,
+        hand: usize,
+        annotate: bool,
+        cli: &mut impl super::MessageProxy,
+    ) {
         let user = &self.hands[hand].player;
         let last = if self.last_turns.is_some() {
             " *last*"
@@ -556,14 +1200,37 @@ impl Game {
         };
 
         // show some states about the general game state
+        let efficiency = match self.efficiency() {
+            Some(e) => format!(", {e:.2} cards per clue"),
+            None => String::new(),
+        };
         cli.send(
             user,
             &format!(
-                ":hourglass: {}; *{}* :information_source: and {} :bomb: remain.",
-                setup, self.clues, self.lives
+                ":hourglass: {}; *{}* :information_source: and {} :bomb: remain (pace {}{}).",
+                setup,
+                self.clues,
+                self.lives,
+                self.pace(),
+                efficiency
             ),
         );
 
+        if self.last_turns.is_none() {
+            match self.pace().cmp(&0) {
+                Ordering::Equal => cli.send(
+                    user,
+                    ":warning: *Pace is zero, so any further discard will lower the max score!*",
+                ),
+                Ordering::Less => cli.send(
+                    user,
+                    ":warning: *Pace is negative, so there aren't enough turns left to finish \
+                     every stack, and every further discard lowers the max score again!*",
+                ),
+                Ordering::Greater => {}
+            }
+        }
+
         if !self.deck.is_empty() && self.deck.len() < 2 * self.hands.len() {
             match self.deck.len() {
                 0 => cli.send(
@@ -581,34 +1248,36 @@ impl Game {
             }
         }
 
-        let stacks: Vec<_> = COLOR_ORDER
-            .iter()
-            .map(|&color| {
-                if let Some(top) = self.played.get(&color) {
-                    format!("{color} {top}")
-                } else {
-                    format!("{color} :zero:")
-                }
-            })
-            .collect();
-
         if self.turn == hand {
-            cli.send(user, &format!("Played:\n{}", stacks.join("  |  ")));
+            cli.send(
+                user,
+                &format!(
+                    "Played ({} points, at most {} possible):\n{}",
+                    self.score(),
+                    self.max_score(),
+                    self.stacks()
+                ),
+            );
 
             // it is our turn.
             // show what we know about our hand, and the hands of the following players
 
-            cli.send(user, "Your hand, as far as you know, is:");
-            let known: Vec<_> = self.hands[hand]
-                .cards
-                .iter()
-                .enumerate()
-                .map(|(i, card)| format!("{}: {}", i + 1, card.known()))
-                .collect();
-            cli.send(user, &known.join("  |  "));
+            if self.open_hands {
+                let (cards, known) = self.hand_rows(hand, hand, annotate);
+                cli.send(user, "Your hand, which everyone can see in this game, is:");
+                cli.send(user, &format!("{cards} in hand\n{known} known"));
+            } else {
+                cli.send(user, "Your hand, as far as you know, is:");
+                let known: Vec<_> = self.hands[hand]
+                    .cards()
+                    .enumerate()
+                    .map(|(i, _)| format!("{}: {}", i + 1, self.known_by(hand, i, hand)))
+                    .collect();
+                cli.send(user, &known.join("  |  "));
+            }
 
             cli.send(user, "");
-            self.show_hands(user, true, cli);
+            self.show_hands(user, true, annotate, cli);
 
             cli.send(
                 user,
@@ -0,0 +1,130 @@
+//! Curated endgame positions for players to solve.
+//!
+//! Puzzles are written in JSON, with cards given as a color letter followed by a number (e.g.,
+//! `"r5"` for the red five), and clues as a color name or a number (e.g., `"red"` or `"5"`).
+
+use super::builder::BuildError;
+use super::{Clue, Color, Game, Number, NUMBERS};
+use serde::{Deserialize, Serialize};
+
+/// The most cards a puzzle may leave in the deck.
+///
+/// Puzzles are endgames, and their best score is checked with the [solver](super::solver), which
+/// tries every line of play and so gets slow quickly as the deck grows.
+pub const MAX_DECK: usize = 5;
+
+/// A position to play out, and the best score the team can get from it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub name: String,
+    pub description: String,
+    /// The cards in each player's hand, from left to right.
+    pub hands: Vec<Vec<String>>,
+    /// The cards left in the deck, in the order they will be drawn; at most [`MAX_DECK`] of them.
+    #[serde(default)]
+    pub deck: Vec<String>,
+    /// The top card of each stack that has been started.
+    #[serde(default)]
+    pub played: Vec<String>,
+    /// The cards in the discard pile.
+    ///
+    /// Any card not placed elsewhere is also discarded.
+    #[serde(default)]
+    pub discarded: Vec<String>,
+    /// Clues the players have been given, as the index of the player and the clue.
+    #[serde(default)]
+    pub clued: Vec<(usize, String)>,
+    #[serde(default = "max_clues")]
+    pub clues: usize,
+    #[serde(default = "max_lives")]
+    pub lives: usize,
+    /// The index of the player whose turn it is.
+    #[serde(default)]
+    pub turn: usize,
+    /// The best score the team can get.
+    pub best: usize,
+}
+
+fn max_clues() -> usize {
+    8
+}
+
+fn max_lives() -> usize {
+    3
+}
+
+/// The reasons a puzzle can't be set up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// This isn't a color letter followed by a number.
+    Card(String),
+    /// This isn't a color name or a number.
+    Clue(String),
+    /// The position can't happen.
+    Build(BuildError),
+}
+
+fn card(card: &str) -> Result<(Color, Number), PuzzleError> {
+    let mut chars = card.chars();
+    let color = match chars.next() {
+        Some('r') => Color::Red,
+        Some('g') => Color::Green,
+        Some('w') => Color::White,
+        Some('b') => Color::Blue,
+        Some('y') => Color::Yellow,
+        _ => return Err(PuzzleError::Card(card.to_string())),
+    };
+    let number = match (chars.next().and_then(|n| n.to_digit(10)), chars.next()) {
+        (Some(n @ 1..=5), None) => NUMBERS[n as usize - 1],
+        _ => return Err(PuzzleError::Card(card.to_string())),
+    };
+    Ok((color, number))
+}
+
+fn cards(cards: &[String]) -> Result<Vec<(Color, Number)>, PuzzleError> {
+    cards.iter().map(|c| card(c)).collect()
+}
+
+fn clue(clue: &str) -> Result<Clue, PuzzleError> {
+    Ok(match clue {
+        "red" => Clue::Color(Color::Red),
+        "green" => Clue::Color(Color::Green),
+        "white" => Clue::Color(Color::White),
+        "blue" => Clue::Color(Color::Blue),
+        "yellow" => Clue::Color(Color::Yellow),
+        n => match n.parse::<usize>() {
+            Ok(n @ 1..=5) => Clue::Number(NUMBERS[n - 1]),
+            _ => return Err(PuzzleError::Clue(clue.to_string())),
+        },
+    })
+}
+
+impl Puzzle {
+    /// The number of players the puzzle is for.
+    pub fn players(&self) -> usize {
+        self.hands.len()
+    }
+
+    /// Set up the puzzle for the given players.
+    pub fn build<'a>(
+        &self,
+        players: impl IntoIterator<Item = &'a str>,
+    ) -> Result<Game, PuzzleError> {
+        let mut builder = Game::builder(players)
+            .deck(cards(&self.deck)?)
+            .discarded(cards(&self.discarded)?)
+            .clues(self.clues)
+            .lives(self.lives)
+            .turn(self.turn);
+        for (player, hand) in self.hands.iter().enumerate() {
+            builder = builder.hand(player, cards(hand)?);
+        }
+        for (color, number) in cards(&self.played)? {
+            builder = builder.played(color, number);
+        }
+        for (player, c) in &self.clued {
+            builder = builder.clued(*player, clue(c)?);
+        }
+        builder.build().map_err(PuzzleError::Build)
+    }
+}
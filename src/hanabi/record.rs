@@ -0,0 +1,58 @@
+//! Recording games so they can be replayed after the fact.
+//!
+//! A game is fully determined by who played it, the order of the deck, and the actions taken, so
+//! that is all a [`Record`] keeps. From it, the game can be recreated as it was at any turn.
+
+use super::components::Deck;
+use super::{Action, ActionError, Color, Game, Number};
+use serde::{Deserialize, Serialize};
+
+/// Everything needed to replay a game.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    /// The players, in turn order.
+    pub players: Vec<String>,
+    /// The whole deck before any cards were dealt, in the order the cards were drawn.
+    pub deck: Vec<(Color, Number)>,
+    /// Every action taken, in order.
+    pub actions: Vec<Action>,
+}
+
+impl Record {
+    /// The number of turns taken in the game.
+    pub fn turns(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Recreate the game as it was after the first `turns` actions were taken.
+    ///
+    /// Fails if one of those actions was not legal, which can only happen if the record did not
+    /// come from a real game.
+    pub fn replay(&self, turns: usize) -> Result<Game, ActionError> {
+        let mut game = Game::with_deck(
+            self.players.iter().map(|p| &**p),
+            Deck::from_order(self.deck.iter().copied()),
+        );
+        for &action in self.actions.iter().take(turns) {
+            game.apply(action)?;
+        }
+        Ok(game)
+    }
+}
+
+impl Game {
+    /// A record of this game so far, from which it can be replayed.
+    ///
+    /// Returns `None` if the game didn't start from a deal, such as if it was set up with a
+    /// [`Builder`](super::builder::Builder).
+    pub fn record(&self) -> Option<Record> {
+        if self.dealt.is_empty() {
+            return None;
+        }
+        Some(Record {
+            players: self.players().map(String::from).collect(),
+            deck: self.dealt.clone(),
+            actions: self.history.clone(),
+        })
+    }
+}
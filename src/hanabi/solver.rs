@@ -0,0 +1,155 @@
+//! Working out the best score a team could get from a position.
+//!
+//! Towards the end of a game, there are few enough moves left that we can try every one of them.
+//! The solver here assumes everyone can see every card, including their own and those left in the
+//! deck, so it tells you what was possible rather than what a team could have known to do.
+
+use super::record::Record;
+use super::{Color, Game, Number};
+
+/// The parts of a game that matter to how it can play out.
+#[derive(Clone)]
+struct State {
+    hands: Vec<Vec<(Color, Number)>>,
+    /// The cards left in the deck, with the next one to be drawn last.
+    deck: Vec<(Color, Number)>,
+    played: [usize; 5],
+    clues: usize,
+    lives: usize,
+    turn: usize,
+    last_turns: Option<usize>,
+}
+
+impl State {
+    fn new(game: &Game) -> Self {
+        let mut deck: Vec<_> = game.deck.cards().map(|c| (c.color, c.number)).collect();
+        deck.reverse();
+        State {
+            hands: game
+                .hands
+                .iter()
+                .map(|hand| hand.cards().map(|c| (c.color, c.number)).collect())
+                .collect(),
+            deck,
+            played: game.played.map(|n| n.map_or(0, |n| n.as_usize())),
+            clues: game.clues,
+            lives: game.lives,
+            turn: game.turn,
+            last_turns: game.last_turns,
+        }
+    }
+
+    fn score(&self) -> usize {
+        self.played.iter().sum()
+    }
+
+    fn is_over(&self) -> bool {
+        self.lives == 0 || self.score() == 25 || self.last_turns == Some(self.hands.len())
+    }
+
+    /// The most cards that can still be played.
+    ///
+    /// Every play draws a card, so once the deck runs out only the last round is left.
+    fn plays_left(&self) -> usize {
+        match self.last_turns {
+            Some(taken) => self.hands.len() - taken,
+            None => self.deck.len() + self.hands.len(),
+        }
+    }
+
+    /// Move on to the next player, drawing a card for the current one if they used one up.
+    fn end_turn(&mut self, drew: bool) {
+        if drew {
+            if let Some(card) = self.deck.pop() {
+                self.hands[self.turn].push(card);
+            }
+        }
+        if self.lives == 0 {
+            return;
+        }
+        self.turn = (self.turn + 1) % self.hands.len();
+        if let Some(ref mut taken) = self.last_turns {
+            *taken += 1;
+        } else if self.deck.is_empty() {
+            self.last_turns = Some(0);
+        }
+    }
+
+    /// The best score reachable from here, or `at_least` if nothing better can be found.
+    fn best(&self, mut at_least: usize, max: usize) -> usize {
+        let score = self.score();
+        if self.is_over() {
+            return score.max(at_least);
+        }
+        if (score + self.plays_left()).min(max) <= at_least {
+            // nothing here can beat what we've already found
+            return at_least;
+        }
+
+        let me = self.turn;
+        for slot in 0..self.hands[me].len() {
+            let mut next = self.clone();
+            let (color, number) = next.hands[me].remove(slot);
+            let stack = &mut next.played[color as usize];
+            if *stack + 1 == number.as_usize() {
+                *stack += 1;
+                if number == Number::Five && next.clues < 8 {
+                    next.clues += 1;
+                }
+            } else {
+                next.lives -= 1;
+            }
+            next.end_turn(true);
+            at_least = next.best(at_least, max);
+
+            if self.clues < 8 {
+                let mut next = self.clone();
+                next.hands[me].remove(slot);
+                next.clues += 1;
+                next.end_turn(true);
+                at_least = next.best(at_least, max);
+            }
+        }
+
+        // with everything known, all a clue does is pass the turn
+        let can_clue = (0..self.hands.len()).any(|p| p != me && !self.hands[p].is_empty());
+        if self.clues > 0 && can_clue {
+            let mut next = self.clone();
+            next.clues -= 1;
+            next.end_turn(false);
+            at_least = next.best(at_least, max);
+        }
+
+        at_least
+    }
+}
+
+/// The best score the team can get from the current position if everyone could see every card.
+///
+/// This tries every possible line of play, so the time it takes grows exponentially with the
+/// number of turns left. It is quick once the deck is (nearly) empty.
+pub fn best_score(game: &Game) -> usize {
+    let state = State::new(game);
+    state.best(state.score(), game.max_score())
+}
+
+/// The last turn from which the team could have gotten a higher score than they did, if everyone
+/// could see every card, along with that score.
+///
+/// Only turns from when the deck ran out are considered, since earlier ones take too long to
+/// search.
+pub fn missed(record: &Record) -> Option<(usize, usize)> {
+    let end = record.replay(record.turns()).ok()?;
+    let scored = end.score();
+    for turn in (0..record.turns()).rev() {
+        let game = record.replay(turn).ok()?;
+        if !game.deck.is_empty() {
+            break;
+        }
+        let best = best_score(&game);
+        if best > scored {
+            return Some((turn, best));
+        }
+    }
+    None
+}
@@ -0,0 +1,397 @@
+//! Automated players.
+//!
+//! A [`Strategy`] is given an [`Observation`] of the game from the point of view of the player
+//! whose turn it is, and decides which [`Action`] that player should take. This lets us drive
+//! entire games without any humans (or Slack) involved, which is handy for testing bots and for
+//! evaluating rule variants offline.
+
+use super::inference;
+use super::{Action, Clue, Color, Game, Knowledge, Number, COLOR_ORDER, NUMBERS};
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use std::ops::Deref;
+
+/// Something that can decide what move to make in a game of Hanabi.
+pub trait Strategy {
+    /// Choose the action to take given what the current player can see.
+    fn decide(&mut self, view: &Observation<'_>) -> Action;
+}
+
+/// The state of a game as seen by one player.
+///
+/// This only gives access to information the player is legally allowed to have: the cards in the
+/// other players' hands, the clues given to every card, the played stacks, the discard pile, and
+/// the token counts. The identities of the player's own cards are hidden.
+pub struct Observation<'a> {
+    game: &'a Game,
+    me: usize,
+}
+
+impl<'a> Observation<'a> {
+    pub(super) fn new(game: &'a Game, me: usize) -> Self {
+        Observation { game, me }
+    }
+
+    /// The hand index of the observing player.
+    pub fn me(&self) -> usize {
+        self.me
+    }
+
+    /// The number of players in the game.
+    pub fn players(&self) -> usize {
+        self.game.hands.len()
+    }
+
+    /// The number of cards in the `player`'th player's hand.
+    pub fn hand_size(&self, player: usize) -> usize {
+        self.game.hands[player].len()
+    }
+
+    /// The identity of the `slot`th card in `player`'s hand.
+    ///
+    /// Returns `None` for the observing player's own cards.
+    pub fn card(&self, player: usize, slot: usize) -> Option<(Color, Number)> {
+        if player == self.me {
+            return None;
+        }
+        self.game.hands[player]
+            .get(slot)
+            .map(|card| (card.color, card.number))
+    }
+
+    /// The id of the `slot`th card in `player`'s hand.
+    ///
+    /// Every card has a distinct id that stays the same for the whole game, even as the card moves
+    /// between slots. Cards are numbered in the order they are drawn, starting at 0.
+    pub fn card_id(&self, player: usize, slot: usize) -> Option<usize> {
+        self.game.hands[player].get(slot).map(|card| card.id)
+    }
+
+    /// What `player` knows about the `slot`th card in their hand from the clues they've been given.
+    pub fn knowledge(&self, player: usize, slot: usize) -> Option<Knowledge> {
+        self.game.hands[player].get(slot).map(|card| card.knowledge)
+    }
+
+    /// The highest card played on the `color` stack, if any.
+    pub fn played(&self, color: Color) -> Option<Number> {
+        self.game.played[color as usize]
+    }
+
+    /// Returns true if the given card could be played right now.
+    pub fn is_playable(&self, color: Color, number: Number) -> bool {
+        self.game.is_playable(color, number)
+    }
+
+    /// All the cards in the discard pile.
+    pub fn discards(&self) -> impl Iterator<Item = (Color, Number)> + use<'a> {
+        let discard = &self.game.discard;
+        COLOR_ORDER.iter().flat_map(move |&color| {
+            NUMBERS.iter().flat_map(move |&number| {
+                let n = discard[color as usize][number.as_usize() - 1];
+                std::iter::repeat_n((color, number), usize::from(n))
+            })
+        })
+    }
+
+    /// The number of discarded cards with the given identity.
+    pub fn discarded(&self, color: Color, number: Number) -> usize {
+        usize::from(self.game.discard[color as usize][number.as_usize() - 1])
+    }
+
+    /// The number of clue tokens available.
+    pub fn clue_tokens(&self) -> usize {
+        self.game.clues
+    }
+
+    /// The number of lives left.
+    pub fn lives(&self) -> usize {
+        self.game.lives
+    }
+
+    /// The number of cards left in the deck.
+    pub fn deck_len(&self) -> usize {
+        self.game.deck.len()
+    }
+
+    /// Returns true if the deck has run out and this is the last round.
+    pub fn is_last_round(&self) -> bool {
+        self.game.last_turns.is_some()
+    }
+
+    /// Every action the observing player could legally take if it were their turn.
+    ///
+    /// Which actions are legal only depends on information the player has, so this does not
+    /// reveal anything they couldn't work out for themselves.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        assert_eq!(self.me, self.game.turn, "it is not the observer's turn");
+        self.game.legal_actions()
+    }
+}
+
+/// The state of a game as seen by one player who can also see their own cards.
+///
+/// This is cheating! It's only here so that we can have oracle strategies to compare against, and
+/// so that tests can check what players infer against the truth. Strategies are only ever handed
+/// an [`Observation`], so they can't get one of these unless they build it themselves.
+pub struct OracleObservation<'a> {
+    view: Observation<'a>,
+}
+
+impl<'a> OracleObservation<'a> {
+    pub(super) fn new(game: &'a Game, me: usize) -> Self {
+        OracleObservation {
+            view: Observation::new(game, me),
+        }
+    }
+
+    /// Look at the identity of the observer's own `slot`th card.
+    pub fn peek(&self, slot: usize) -> (Color, Number) {
+        let card = self.view.game.hands[self.view.me].get(slot).unwrap();
+        (card.color, card.number)
+    }
+}
+
+impl<'a> Deref for OracleObservation<'a> {
+    type Target = Observation<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.view
+    }
+}
+
+/// A strategy that picks uniformly at random among all legal moves.
+pub struct Random<R> {
+    rng: R,
+}
+
+impl<R: Rng> Random<R> {
+    pub fn new(rng: R) -> Self {
+        Random { rng }
+    }
+}
+
+impl<R: Rng> Strategy for Random<R> {
+    fn decide(&mut self, view: &Observation<'_>) -> Action {
+        *view.legal_actions().choose(&mut self.rng).unwrap()
+    }
+}
+
+/// A strategy that looks at its own cards, to give a rough upper bound for how well a strategy
+/// could hope to do.
+///
+/// It plays whenever it can, gets rid of useless cards when it may, stalls with pointless clues
+/// when there are clue tokens to spare, and otherwise discards its least valuable card.
+#[derive(Default)]
+pub struct Cheater;
+
+impl Strategy for Cheater {
+    fn decide(&mut self, view: &Observation<'_>) -> Action {
+        let view = OracleObservation::new(view.game, view.me);
+        let me = view.me();
+        let hand: Vec<_> = (0..view.hand_size(me))
+            .map(|slot| view.peek(slot))
+            .collect();
+
+        if let Some(slot) = hand.iter().position(|&(c, n)| view.is_playable(c, n)) {
+            return Action::Play(slot);
+        }
+
+        let is_trash = |&(c, n): &(Color, Number)| {
+            view.played(c)
+                .is_some_and(|top| n.as_usize() <= top.as_usize())
+        };
+        if view.clue_tokens() < 8 {
+            if let Some(slot) = hand.iter().position(is_trash) {
+                return Action::Discard(slot);
+            }
+        }
+
+        if view.clue_tokens() > 0 {
+            let player = (me + 1) % view.players();
+            let (color, _) = view.card(player, 0).unwrap();
+            return Action::Clue {
+                player,
+                clue: Clue::Color(color),
+            };
+        }
+
+        // we have to discard something -- pick the card whose loss hurts the least
+        let is_critical = |&(c, n): &(Color, Number)| view.discarded(c, n) + 1 == n.copies();
+        let slot = (0..hand.len())
+            .filter(|&slot| !is_critical(&hand[slot]))
+            .max_by_key(|&slot| hand[slot].1.as_usize())
+            .unwrap_or(0);
+        Action::Discard(slot)
+    }
+}
+
+/// Why [`Heuristic`] thinks a move is worth making.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reason {
+    /// The card is playable with the given probability.
+    MayBePlayable(f64),
+    /// The card can never be played.
+    Useless,
+    /// The card is still needed with the given probability.
+    MayBeNeeded(f64),
+    /// The clue tells the player about these playable cards.
+    Playable(Vec<(Color, Number)>),
+    /// The clue touches the last copy of this card before it would be discarded.
+    Saves((Color, Number)),
+    /// The clue doesn't tell the player anything useful, but spares the team a discard.
+    Stall,
+}
+
+/// A legal move, and how good [`Heuristic`] thinks it is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub action: Action,
+    /// How good the move is, roughly in points; only useful for comparing moves.
+    pub value: f64,
+    pub reason: Reason,
+}
+
+/// A strategy that plays like a sensible beginner, using only what the player is allowed to know.
+///
+/// It plays cards it knows are playable, clues other players about playable cards and about
+/// critical cards that are next to be discarded, and otherwise discards the card it is least
+/// likely to need. Since it never looks at its own cards, its reasoning can be shown to players
+/// who want help deciding on a move.
+#[derive(Default)]
+pub struct Heuristic;
+
+impl Heuristic {
+    /// Every legal move for the observing player, best first.
+    pub fn suggestions(view: &Observation<'_>) -> Vec<Suggestion> {
+        let me = view.me();
+        let hand = inference::hand(view);
+        let is_trash = |c: Color, n: Number| {
+            view.played(c)
+                .is_some_and(|top| n.as_usize() <= top.as_usize())
+        };
+        let is_critical =
+            |c: Color, n: Number| !is_trash(c, n) && view.discarded(c, n) + 1 == n.copies();
+        let chance = |slot: usize, f: &dyn Fn(Color, Number) -> bool| -> f64 {
+            hand[slot]
+                .likeliest()
+                .into_iter()
+                .filter(|&((c, n), _)| f(c, n))
+                .map(|(_, p)| p)
+                .sum()
+        };
+        let is_touched = |player: usize, slot: usize| {
+            let knowledge = view.knowledge(player, slot).unwrap();
+            knowledge.color().is_some() || knowledge.number().is_some()
+        };
+        // whether the clues on a card alone say that it's playable
+        let knows_playable = |k: Knowledge| {
+            k.colors()
+                .all(|c| k.numbers().all(|n| view.is_playable(c, n)))
+        };
+        // the card a player would discard next: their oldest one that hasn't been clued
+        let chop =
+            |player: usize| (0..view.hand_size(player)).find(|&slot| !is_touched(player, slot));
+
+        let mut suggestions: Vec<_> = view
+            .legal_actions()
+            .into_iter()
+            .map(|action| match action {
+                Action::Play(slot) => {
+                    let p = chance(slot, &|c, n| view.is_playable(c, n));
+                    let misplay = if view.lives() == 1 { 100.0 } else { 8.0 };
+                    let value = if p > 0.999 {
+                        10.0
+                    } else {
+                        10.0 * p - misplay * (1.0 - p)
+                    };
+                    Suggestion {
+                        action,
+                        value,
+                        reason: Reason::MayBePlayable(p),
+                    }
+                }
+                Action::Discard(slot) => {
+                    let needed = chance(slot, &|c, n| !is_trash(c, n));
+                    if needed < 0.001 {
+                        return Suggestion {
+                            action,
+                            value: 4.0,
+                            reason: Reason::Useless,
+                        };
+                    }
+                    let critical = chance(slot, &|c, n| is_critical(c, n));
+                    let mut value = 1.0 - 2.0 * needed - 10.0 * critical;
+                    if is_touched(me, slot) {
+                        // someone spent a clue on it for a reason
+                        value -= 3.0;
+                    } else if chop(me) == Some(slot) {
+                        value += 0.5;
+                    }
+                    Suggestion {
+                        action,
+                        value,
+                        reason: Reason::MayBeNeeded(needed),
+                    }
+                }
+                Action::Clue { player, clue } => {
+                    let mut playable = Vec::new();
+                    let mut saves = None;
+                    let mut value = -1.0;
+                    for slot in 0..view.hand_size(player) {
+                        let (c, n) = view.card(player, slot).unwrap();
+                        if !clue.touches(c, n) {
+                            continue;
+                        }
+                        let before = view.knowledge(player, slot).unwrap();
+                        let mut after = before;
+                        after.clued(clue, true);
+                        if view.is_playable(c, n) && !knows_playable(before) {
+                            if knows_playable(after) {
+                                value += 7.0;
+                            } else if !is_touched(player, slot) {
+                                value += 3.0;
+                            } else {
+                                continue;
+                            }
+                            playable.push((c, n));
+                        } else if is_touched(player, slot) {
+                            continue;
+                        } else if is_critical(c, n) && chop(player) == Some(slot) {
+                            saves = Some((c, n));
+                            value += 5.0;
+                        } else if is_trash(c, n) {
+                            value -= 2.0;
+                        } else {
+                            value -= 1.0;
+                        }
+                    }
+                    // prefer helping the players who go soon
+                    let distance = (player + view.players() - me) % view.players();
+                    value -= 0.1 * distance as f64;
+
+                    let reason = if !playable.is_empty() {
+                        Reason::Playable(playable)
+                    } else if let Some(card) = saves {
+                        Reason::Saves(card)
+                    } else {
+                        value = value.min(0.0) + view.clue_tokens() as f64 / 4.0 - 2.5;
+                        Reason::Stall
+                    };
+                    Suggestion {
+                        action,
+                        value,
+                        reason,
+                    }
+                }
+            })
+            .collect();
+        suggestions.sort_by(|a, b| b.value.total_cmp(&a.value));
+        suggestions
+    }
+}
+
+impl Strategy for Heuristic {
+    fn decide(&mut self, view: &Observation<'_>) -> Action {
+        Self::suggestions(view)[0].action
+    }
+}
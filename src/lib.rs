@@ -1,11 +1,12 @@
 use eyre::Context;
-use hanabi::{Clue, Color, Game, Number};
+use hanabi::hanab_live::ImportError;
+use hanabi::{Action, ActionError, Clue, Color, Game, Number};
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 use slack_morphism::prelude::*;
 use std::collections::{HashMap, HashSet, VecDeque};
 
-mod hanabi;
+pub mod hanabi;
 
 // Welcome to the Hanabi bot code.
 //
@@ -44,7 +45,8 @@ impl Hanabi {
         u: SlackUserId,
         messages: &mut impl MessageProxy,
     ) -> eyre::Result<()> {
-        let mut command_parts = t.split_whitespace();
+        let messages = &mut HumansOnly(messages);
+        let mut command_parts = t.split_whitespace().peekable();
         let Some(command) = command_parts.next() else {
             // empty message
             return Ok(());
@@ -58,6 +60,16 @@ impl Hanabi {
             return Ok(());
         }
 
+        if let Some(game_id) = self.dropped.remove(&u) {
+            messages.send(
+                &u.0,
+                &format!(
+                    "Sorry, game #{game_id} was saved by an older version of me that I can't read, \
+                     so it has ended. You're waiting for a new game now."
+                ),
+            );
+        }
+
         match &*command.to_lowercase() {
             "join" => {
                 if self.playing_users.insert(u.clone()) {
@@ -139,6 +151,151 @@ impl Hanabi {
                 }
                 messages.send(&u.0, &out);
             }
+            "annotate" => {
+                let on = match command_parts.next() {
+                    Some("on") => true,
+                    Some("off") => false,
+                    None => !self.annotating.contains(&u),
+                    Some(_) => {
+                        messages.send(&u.0, "You can turn annotations `on` or `off`.");
+                        return Ok(());
+                    }
+                };
+                if on {
+                    self.annotating.insert(u.clone());
+                    messages.send(
+                        &u.0,
+                        "I'll mark critical cards with :rotating_light: and cards that \
+                         can never be played with :wastebasket: in other players' hands.",
+                    );
+                } else {
+                    self.annotating.remove(&u);
+                    messages.send(&u.0, "I'll stop marking cards in other players' hands.");
+                }
+                self.save().await.context("save on annotate")?;
+            }
+            "review" => {
+                let game_id = command_parts
+                    .next()
+                    .and_then(|id| id.trim_start_matches('#').parse::<usize>().ok());
+                let Some(game_id) = game_id.filter(|id| self.finished.contains_key(id)) else {
+                    messages.send(
+                        &u.0,
+                        "Which game? Give the number of a game that has ended, like `review 3`.",
+                    );
+                    return Ok(());
+                };
+                self.reviewing.insert(u.clone(), (game_id, 0));
+                self.show_review(&u, messages);
+            }
+            "import" => {
+                let record = match hanab_live_json(t) {
+                    Some(Ok(json)) => hanabi::hanab_live::import(&json),
+                    Some(Err(e)) => {
+                        messages.send(&u.0, &format!("I can't read that game: {e}"));
+                        return Ok(());
+                    }
+                    None => {
+                        messages.send(
+                            &u.0,
+                            "Give me a game in hanab.live's JSON format, like `import {...}`.",
+                        );
+                        return Ok(());
+                    }
+                };
+                match record {
+                    Ok(record) => {
+                        let game_id = self.ngames;
+                        self.ngames += 1;
+                        self.finished.insert(game_id, record);
+                        messages.send(
+                            &u.0,
+                            &format!("Imported as game #{game_id}; use `review {game_id}` to look through it."),
+                        );
+                        self.save().await.context("save after import")?;
+                    }
+                    Err(e) => messages.send(
+                        &u.0,
+                        &format!("I can't replay that game: {}", explain_import(e)),
+                    ),
+                }
+            }
+            "puzzle" => {
+                let Some(n) = command_parts.next() else {
+                    let mut out = String::from(
+                        "Solve a puzzle with `puzzle <number> @friend ...`, \
+                         bringing along as many friends as the puzzle needs. \
+                         I'll play any seats you leave empty. These are the puzzles:",
+                    );
+                    for (i, puzzle) in self.puzzles.iter().enumerate() {
+                        out.push_str(&format!(
+                            "\n{}: {} ({} players) {}",
+                            i + 1,
+                            puzzle.name,
+                            puzzle.players(),
+                            puzzle.description
+                        ));
+                    }
+                    messages.send(&u.0, &out);
+                    return Ok(());
+                };
+
+                let Some(i) = n
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .filter(|&i| i < self.puzzles.len())
+                else {
+                    messages.send(
+                        &u.0,
+                        "There's no such puzzle. Use `puzzle` to see which ones there are.",
+                    );
+                    return Ok(());
+                };
+                let mut players = vec![u.clone()];
+                players.extend(command_parts.map(|p| {
+                    SlackUserId(p.trim_start_matches("<@").trim_end_matches('>').to_string())
+                }));
+                self.start_puzzle(i, players, messages)
+                    .await
+                    .context("start puzzle")?;
+            }
+            "export" => {
+                let game_id = command_parts
+                    .next()
+                    .and_then(|id| id.trim_start_matches('#').parse::<usize>().ok());
+                match game_id.map(|id| (id, self.export(id))) {
+                    Some((_, Ok(Some(json)))) => messages.send(&u.0, &format!("```{json}```")),
+                    Some((game_id, Err(e))) => {
+                        eprintln!("failed to export game #{game_id}: {e:?}");
+                        messages.send(
+                            &u.0,
+                            &format!("Sorry, something went wrong exporting game #{game_id}."),
+                        );
+                    }
+                    _ => messages.send(
+                        &u.0,
+                        "Which game? Give the number of a game that has ended, like `export 3`.",
+                    ),
+                }
+            }
+            "next" | "prev" | "turn" if self.reviewing.contains_key(&u) => {
+                let (game_id, turn) = self.reviewing[&u];
+                let turns = self.finished[&game_id].turns();
+                let turn = match &*command.to_lowercase() {
+                    "next" => turn + 1,
+                    "prev" => turn.saturating_sub(1),
+                    _ => match command_parts.next().and_then(|n| n.parse().ok()) {
+                        Some(n) => n,
+                        None => {
+                            messages.send(&u.0, "Which turn? Give its number, like `turn 12`.");
+                            return Ok(());
+                        }
+                    },
+                };
+                self.reviewing.insert(u.clone(), (game_id, turn.min(turns)));
+                self.show_review(&u, messages);
+            }
             "help" => {
                 let out = if self.playing_users.contains(&u) {
                     "Oh, so you're confused? I'm so sorry to hear that.\n\
@@ -149,10 +306,31 @@ impl Hanabi {
                  To `clue`, you give the player you are cluing (`@player`), \
                  and the clue you want to give (e.g., `red`, `one`).\n\
                  \n\
-                 To look around, you can use `hands`, `deck`, or `discards`. \
+                 To look around, you can use `hands`, `deck`, `discards`, `remaining`, \
+                 `card`, `think`, or `clues`. \
                  `hands` will tell you what each player has and knows, `deck` will \
                  show you the number of cards left, and `discards` will show \
-                 you the discard pile. If everything goes south, you can always use \
+                 you the discard pile. `remaining` lists the cards you can't see anywhere, \
+                 which must be in the deck or your hand. `card` followed by a position will list \
+                 everything that card in your hand could still be, and `think` \
+                 will tell you how likely each of your cards is to be what, \
+                 given all the cards you can see. \
+                 `clues` lists which clues touched or ruled out each card, and who gave them. \
+                 You can keep private notes on cards with `note 2 probably red` for your own \
+                 cards, or `note @player 2 chop` for someone else's; \
+                 a note with no text removes it. \
+                 `annotate` toggles marking critical and useless cards in other players' hands, \
+                 and `review` followed by a game number lets you step through a game that has \
+                 ended. `export` followed by a game number gives you the game in the format \
+                 <https://hanab.live> uses, so you can load it into their replay tools. \
+                 Going the other way, `import` followed by a hanab.live game adds it for \
+                 you to `review`, and `start` followed by one deals its deck. \
+                 `puzzle` lists endgame puzzles you can try to solve, alone or with friends. \
+                 To practice, start a game with `start learning`; \
+                 in it, `suggest` will tell you what I would do on your turn, and why. \
+                 With `start open`, everyone can see their own cards too, \
+                 which helps while learning the rules, but the game won't be ranked. \
+                 If everything goes south, you can always use \
                  `quit` to give up.\n\
                  \n\
                  Should you no longer wish to play, write `leave`.\n\
@@ -177,6 +355,65 @@ impl Hanabi {
                     // known user made a move in a game
                 } else if self.playing_users.contains(&u) && cmd == "start" {
                     // known user is trying to start a game
+                    if let Some(json) = hanab_live_json(t) {
+                        // they want to play a particular deck
+                        let json = match json {
+                            Ok(json) => json,
+                            Err(e) => {
+                                messages.send(&u.0, &format!("I can't read that deck: {e}"));
+                                return Ok(());
+                            }
+                        };
+                        let deck = match hanabi::hanab_live::import_deck(&json) {
+                            Ok(deck) => deck,
+                            Err(e) => {
+                                messages.send(
+                                    &u.0,
+                                    &format!("I can't play with that deck: {}", explain_import(e)),
+                                );
+                                return Ok(());
+                            }
+                        };
+                        // the deal depends on the number of players, so use the same number
+                        let nplayers = (!json.players.is_empty()).then_some(json.players.len());
+                        if let Some(n) = nplayers {
+                            if !(2..=5).contains(&n) {
+                                messages.send(
+                                    &u.0,
+                                    &format!(
+                                        "I can't play with that deck: {}",
+                                        explain_import(ImportError::Players(n))
+                                    ),
+                                );
+                                return Ok(());
+                            }
+                            if self.waiting.contains(&u) && self.waiting.len() < n {
+                                messages.send(
+                                    &u.0,
+                                    &format!(
+                                        "That deck was dealt to {n} players, \
+                                         so I can only deal it once {n} players, \
+                                         including you, are waiting for a game."
+                                    ),
+                                );
+                                return Ok(());
+                            }
+                        }
+                        self.start_game(Some(&u), nplayers, Some(deck), false, false, messages)
+                            .await
+                            .context("start game with deck")?;
+                        return Ok(());
+                    }
+
+                    let (mut learning, mut open_hands) = (false, false);
+                    while let Some(option) =
+                        command_parts.next_if(|&a| a == "learning" || a == "open")
+                    {
+                        match option {
+                            "learning" => learning = true,
+                            _ => open_hands = true,
+                        }
+                    }
                     let arg = command_parts.next();
                     let has_arg = arg.is_some();
                     let nplayers = arg.and_then(|n| n.parse().ok());
@@ -188,7 +425,7 @@ impl Hanabi {
                         );
                     } else {
                         // the user wants to start the game even though there aren't enough players
-                        self.start_game(Some(&u), nplayers, messages)
+                        self.start_game(Some(&u), nplayers, None, learning, open_hands, messages)
                             .await
                             .context("start game")?;
                     }
@@ -216,6 +453,82 @@ impl Hanabi {
     }
 }
 
+/// The most notable moments to list after a game, so that the list stays short.
+const MAX_MOMENTS: usize = 5;
+
+/// The hanab.live JSON game given after the command in `text`, if there is one.
+///
+/// Slack users will often paste JSON inside a code block, so we look past the backticks.
+fn hanab_live_json(text: &str) -> Option<Result<hanabi::hanab_live::GameJson, serde_json::Error>> {
+    let (_, rest) = text.trim().split_once(char::is_whitespace)?;
+    let json = rest.trim().trim_matches('`');
+    json.starts_with('{').then(|| serde_json::from_str(json))
+}
+
+/// Explain to a player why the move they tried to make in `game` was rejected with `e`.
+fn explain(game: &Game, e: ActionError) -> String {
+    match e {
+        ActionError::GameOver => "The game is already over.".to_string(),
+        ActionError::NoSuchPlayer => "The player you specified does not exist. \
+             Remember to use Slack's @username tagging."
+            .to_string(),
+        ActionError::ClueToSelf => "You can't give a clue to yourself.".to_string(),
+        ActionError::NoMatchingCards => "None of the cards in that player's hand match that clue, \
+             and you can only give clues that touch at least one card."
+            .to_string(),
+        ActionError::NotEnoughClues => {
+            "There are no clue tokens left, so you cannot clue.".to_string()
+        }
+        ActionError::MaxClues => {
+            "All 8 clue tokens are available, so discard is disallowed.".to_string()
+        }
+        ActionError::NoSuchCard => format!(
+            "The card you specified is not in your hand. \
+             Remember that card indexing starts at 1, and that you have {} cards.",
+            game.hand_size(game.current_player())
+        ),
+    }
+}
+
+/// Explain to a user why a hanab.live game couldn't be imported.
+fn explain_import(e: ImportError) -> String {
+    match e {
+        ImportError::UnknownSuit(suit) => {
+            format!("it uses suit number {suit}, and I only know the five standard suits.")
+        }
+        ImportError::UnknownRank(rank) => {
+            format!("it has a rank of {rank}, and ranks go from 1 to 5.")
+        }
+        ImportError::Variant => format!(
+            "it's played with a variant, and I only know the rules of \"{}\".",
+            hanabi::hanab_live::NO_VARIANT
+        ),
+        ImportError::NotAFullDeck => "its deck isn't exactly one full deck of cards.".to_string(),
+        ImportError::Players(n) => {
+            format!("it has {n} players, and games need between 2 and 5.")
+        }
+        ImportError::UnknownAction(turn) => {
+            format!("move {} is of a kind I don't know about.", turn + 1)
+        }
+        ImportError::CardNotInHand(turn) => format!(
+            "move {} uses a card that isn't in the player's hand.",
+            turn + 1
+        ),
+        ImportError::IllegalAction(turn, e) => {
+            let why = match e {
+                ActionError::GameOver => "the game was already over",
+                ActionError::NoSuchPlayer => "it clues a player who isn't in the game",
+                ActionError::ClueToSelf => "a player clues themselves",
+                ActionError::NoMatchingCards => "the clue doesn't touch any cards",
+                ActionError::NotEnoughClues => "there were no clue tokens left",
+                ActionError::MaxClues => "it discards with all 8 clue tokens available",
+                ActionError::NoSuchCard => "it uses a card that isn't in the player's hand",
+            };
+            format!("move {} isn't legal, since {why}.", turn + 1)
+        }
+    }
+}
+
 #[allow(async_fn_in_trait)]
 pub trait MessageProxy {
     fn send(&mut self, user: &str, text: &str);
@@ -239,6 +552,32 @@ where
     }
 }
 
+/// The name of the seat a bot plays in a puzzle that is short of players, for the `seat`th seat
+/// (0-indexed).
+///
+/// Slack user ids are all uppercase, so these can never clash with a real player.
+fn bot_seat(seat: usize) -> String {
+    format!("bot{}", seat + 1)
+}
+
+/// Returns true if `player` names a seat a bot plays.
+fn is_bot(player: &str) -> bool {
+    player
+        .strip_prefix("bot")
+        .is_some_and(|seat| seat.parse::<usize>().is_ok())
+}
+
+/// A `MessageProxy` that drops every message meant for a bot, since bots have nowhere to read them.
+struct HumansOnly<M>(M);
+
+impl<M: MessageProxy> MessageProxy for HumansOnly<M> {
+    fn send(&mut self, user: &str, text: &str) {
+        if !is_bot(user) {
+            self.0.send(user, text)
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Default)]
 pub struct Hanabi {
     /// id of the bot's user
@@ -261,6 +600,35 @@ pub struct Hanabi {
 
     /// map from each user to the game they are in
     in_game: HashMap<SlackUserId, usize>,
+
+    /// users who want critical and trash cards marked in other players' hands
+    #[serde(default)]
+    annotating: HashSet<SlackUserId>,
+
+    /// records of games that have ended, indexed by game number
+    #[serde(default)]
+    finished: HashMap<usize, hanabi::record::Record>,
+
+    /// the game each user is reviewing, and which turn they are looking at
+    #[serde(default)]
+    reviewing: HashMap<SlackUserId, (usize, usize)>,
+
+    /// the puzzles players can try, loaded at startup
+    #[serde(skip)]
+    puzzles: Vec<hanabi::puzzle::Puzzle>,
+
+    /// the puzzle each running puzzle game was set up from, indexed by game number
+    #[serde(default)]
+    puzzle_games: HashMap<usize, usize>,
+
+    /// running games where players may ask for suggestions, by game number
+    #[serde(default)]
+    learning: HashSet<usize>,
+
+    /// players whose game couldn't be restored at startup, with the game's number, who haven't
+    /// been told yet
+    #[serde(skip)]
+    dropped: HashMap<SlackUserId, usize>,
 }
 
 impl Hanabi {
@@ -272,14 +640,95 @@ impl Hanabi {
             let state_json = tokio::fs::read("state.json")
                 .await
                 .context("read state.json")?;
-            Ok(Some(
-                serde_json::from_reader(&*state_json).context("parse state.json")?,
-            ))
+            let mut state: serde_json::Value =
+                serde_json::from_slice(&state_json).context("parse state.json")?;
+
+            // games saved by an older version of the bot may not be in a format we can read. we'd
+            // rather lose those games than refuse to start at all.
+            if let Some(games) = state.get_mut("games").and_then(|g| g.as_object_mut()) {
+                games.retain(
+                    |id, game| match serde_json::from_value::<Game>(game.clone()) {
+                        Ok(_) => true,
+                        Err(e) => {
+                            eprintln!("warning: dropping game #{id}, which can't be read: {e}");
+                            false
+                        }
+                    },
+                );
+            }
+            let mut hanabi: Self = serde_json::from_value(state).context("parse state.json")?;
+
+            // anyone who was in a dropped game can join a new one, and we'll tell them why their
+            // game went away the next time they message us
+            let orphaned: Vec<_> = hanabi
+                .in_game
+                .iter()
+                .filter(|(_, game_id)| !hanabi.games.contains_key(game_id))
+                .map(|(user, &game_id)| (user.clone(), game_id))
+                .collect();
+            for (user, game_id) in orphaned {
+                hanabi.in_game.remove(&user);
+                hanabi.waiting.push_back(user.clone());
+                hanabi.dropped.insert(user, game_id);
+            }
+            let games = &hanabi.games;
+            hanabi
+                .learning
+                .retain(|game_id| games.contains_key(game_id));
+            hanabi
+                .puzzle_games
+                .retain(|game_id, _| games.contains_key(game_id));
+            Ok(Some(hanabi))
         } else {
             Ok(None)
         }
     }
 
+    /// Load the puzzles players can try from the JSON file at `path`, if it exists.
+    pub async fn load_puzzles(&mut self, path: &str) -> eyre::Result<()> {
+        if !tokio::fs::try_exists(path)
+            .await
+            .with_context(|| format!("check for {path}"))?
+        {
+            println!("no puzzles at {path}");
+            return Ok(());
+        }
+        let json = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("read {path}"))?;
+        let puzzles: Vec<hanabi::puzzle::Puzzle> =
+            serde_json::from_slice(&json).with_context(|| format!("parse {path}"))?;
+
+        // make sure every puzzle can actually be set up, and that its best score is right, since
+        // that's what tells players whether they solved it
+        for (i, puzzle) in puzzles.iter().enumerate() {
+            if puzzle.deck.len() > hanabi::puzzle::MAX_DECK {
+                eyre::bail!(
+                    "puzzle {} in {path} has {} cards in the deck, \
+                     but puzzles are endgames and can have at most {}",
+                    i + 1,
+                    puzzle.deck.len(),
+                    hanabi::puzzle::MAX_DECK
+                );
+            }
+            let names: Vec<_> = (0..puzzle.players()).map(|p| p.to_string()).collect();
+            let game = match puzzle.build(names.iter().map(|s| &**s)) {
+                Ok(game) => game,
+                Err(e) => eyre::bail!("puzzle {} in {path} cannot be set up: {e:?}", i + 1),
+            };
+            let best = hanabi::solver::best_score(&game);
+            if best != puzzle.best {
+                eyre::bail!(
+                    "puzzle {} in {path} says the best score is {}, but it is {best}",
+                    i + 1,
+                    puzzle.best
+                );
+            }
+        }
+        self.puzzles = puzzles;
+        Ok(())
+    }
+
     pub async fn save(&self) -> eyre::Result<()> {
         let state = serde_json::to_vec(self).context("serialize Hanabi state")?;
         tokio::fs::write("state.json", &state)
@@ -317,11 +766,15 @@ impl Hanabi {
     ///
     /// If `user` is not `None`, then `user` tried to force a game to start despite there not being
     /// a full five waiting players. If this is the case, `user` should certainly be included in
-    /// the new game (assuming there are at least two free players).
+    /// the new game (assuming there are at least two free players). In a `learning` game, players
+    /// can ask for suggestions on what to do, and with `open_hands`, they can see their own cards.
     async fn start_game(
         &mut self,
         user: Option<&SlackUserId>,
         users: Option<usize>,
+        deck: Option<Vec<(Color, Number)>>,
+        learning: bool,
+        open_hands: bool,
         msgs: &mut impl MessageProxy,
     ) -> eyre::Result<()> {
         let mut players = Vec::new();
@@ -339,8 +792,8 @@ impl Hanabi {
             }
         }
 
-        let users = users.unwrap_or(5);
-        while players.len() < users && players.len() <= 5 {
+        let users = users.unwrap_or(5).min(5);
+        while players.len() < users {
             if let Some(u) = self.waiting.pop_front() {
                 players.push(u);
             } else {
@@ -360,10 +813,34 @@ impl Hanabi {
             return Ok(());
         }
 
-        let game = Game::new(players.iter().map(|slack_user| &*slack_user.0));
+        let names = players.iter().map(|slack_user| &*slack_user.0);
+        let game = match deck {
+            Some(deck) => match Game::with_deck_order(names, deck) {
+                Some(game) => game,
+                None => {
+                    if let Some(u) = user {
+                        msgs.send(&u.0, "That deck isn't a full deck of cards.");
+                    }
+                    // put everyone back in line where they were
+                    for p in players.into_iter().rev() {
+                        self.waiting.push_front(p);
+                    }
+                    return Ok(());
+                }
+            },
+            None => Game::new(names),
+        };
+        let game = if open_hands {
+            game.with_open_hands()
+        } else {
+            game
+        };
         let game_id = self.ngames;
         self.ngames += 1;
         self.games.insert(game_id, game);
+        if learning {
+            self.learning.insert(game_id);
+        }
 
         println!(
             "starting game #{} with {} users: {:?}",
@@ -384,6 +861,18 @@ impl Hanabi {
                 others.join(", ")
             );
             msgs.send(&p.0, &message);
+            if learning {
+                msgs.send(
+                    &p.0,
+                    "This is a learning game, so you can ask me to `suggest` a move on your turn.",
+                );
+            }
+            if open_hands {
+                msgs.send(
+                    &p.0,
+                    "Everyone can see their own cards in this game, so it won't be ranked.",
+                );
+            }
         }
         for p in players {
             let already_in = self.in_game.insert(p, game_id);
@@ -396,6 +885,95 @@ impl Hanabi {
         Ok(())
     }
 
+    /// Start a game of the `i`th puzzle, with `players` taking the seats in order.
+    async fn start_puzzle(
+        &mut self,
+        i: usize,
+        players: Vec<SlackUserId>,
+        msgs: &mut impl MessageProxy,
+    ) -> eyre::Result<()> {
+        let user = &players[0];
+        let puzzle = &self.puzzles[i];
+        if players.len() > puzzle.players() {
+            msgs.send(
+                &user.0,
+                &format!(
+                    "Puzzle {} is for {} players, so you can bring at most {} friends.",
+                    i + 1,
+                    puzzle.players(),
+                    puzzle.players() - 1
+                ),
+            );
+            return Ok(());
+        }
+        if let Some(p) = players
+            .iter()
+            .find(|&p| !self.playing_users.contains(p) || self.in_game.contains_key(p))
+        {
+            msgs.send(
+                &user.0,
+                &format!("<@{p}> needs to have joined, and not be in a game already."),
+            );
+            return Ok(());
+        }
+        if players
+            .iter()
+            .enumerate()
+            .any(|(i, p)| players[..i].contains(p))
+        {
+            msgs.send(&user.0, "Every player needs their own seat.");
+            return Ok(());
+        }
+
+        // any seats left empty are played by bots
+        let bots: Vec<_> = (players.len()..puzzle.players()).map(bot_seat).collect();
+        let game = puzzle
+            .build(
+                players
+                    .iter()
+                    .map(|p| &*p.0)
+                    .chain(bots.iter().map(|b| &**b)),
+            )
+            .map_err(|e| eyre::eyre!("puzzle {} cannot be set up: {e:?}", i + 1))?;
+        let game_id = self.ngames;
+        self.ngames += 1;
+        self.games.insert(game_id, game);
+        self.puzzle_games.insert(game_id, i);
+        println!("starting puzzle {} as game #{game_id}: {players:?}", i + 1);
+
+        for p in &players {
+            msgs.send(
+                &p.0,
+                &format!(
+                    "You are now solving puzzle {}, \"{}\": {}",
+                    i + 1,
+                    puzzle.name,
+                    puzzle.description
+                ),
+            );
+            if !bots.is_empty() {
+                msgs.send(
+                    &p.0,
+                    &format!(
+                        "I'll play {}, using what that seat is allowed to know.",
+                        bots.iter()
+                            .map(|b| format!("`{b}`"))
+                            .collect::<Vec<_>>()
+                            .join(" and ")
+                    ),
+                );
+            }
+        }
+        self.waiting.retain(|p| !players.contains(p));
+        for p in players {
+            self.in_game.insert(p, game_id);
+        }
+
+        self.progress_game(game_id, msgs)
+            .await
+            .context("progress puzzle")
+    }
+
     /// Handle a turn command by the given `user`.
     async fn handle_move(
         &mut self,
@@ -465,11 +1043,91 @@ impl Hanabi {
                 self.games[&game_id].show_discards(&user.0, msgs);
             }
             Some("hands") => {
-                self.games[&game_id].show_hands(&user.0, false, msgs);
+                let annotate = self.annotating.contains(user);
+                self.games[&game_id].show_hands(&user.0, false, annotate, msgs);
             }
             Some("deck") => {
                 self.games[&game_id].show_deck(&user.0, msgs);
             }
+            Some("remaining") => {
+                self.games[&game_id].show_remaining(&user.0, msgs);
+            }
+            Some("think") => {
+                self.games[&game_id].show_think(&user.0, msgs);
+            }
+            Some("clues") => {
+                self.games[&game_id].show_clues(&user.0, msgs);
+            }
+            Some("suggest" | "hint") => {
+                let game = &self.games[&game_id];
+                if !self.learning.contains(&game_id) {
+                    msgs.send(
+                        &user.0,
+                        "Suggestions are only available in learning games. \
+                         Use `start learning` to start one.",
+                    );
+                } else if game.current_player() != user.0 {
+                    msgs.send(
+                        &user.0,
+                        &format!(
+                            "It's not your turn yet, it's <@{}>'s.",
+                            game.current_player()
+                        ),
+                    );
+                } else {
+                    game.show_suggestion(&user.0, msgs);
+                }
+            }
+            Some("card") => {
+                let card = command.next().and_then(|card| card.parse::<usize>().ok());
+                if card.is_none() || card == Some(0) || command.next().is_some() {
+                    msgs.send(
+                        &user.0,
+                        "Which card? Give its index from the left side of your hand \
+                         (starting at 1), like `card 2`.",
+                    );
+                    return Ok(());
+                }
+                self.games[&game_id].show_card(&user.0, card.unwrap() - 1, msgs);
+            }
+            Some("note") => {
+                let game = &self.games[&game_id];
+                let mut player = game.player_index(&user.0).unwrap();
+                if let Some(p) = command.next_if(|p| p.starts_with("<@") && p.ends_with('>')) {
+                    let Some(p) = game.player_index(&p[2..p.len() - 1]) else {
+                        msgs.send(&user.0, &explain(game, ActionError::NoSuchPlayer));
+                        return Ok(());
+                    };
+                    player = p;
+                }
+                let card = command.next().and_then(|card| card.parse::<usize>().ok());
+                if card.is_none() || card == Some(0) {
+                    msgs.send(
+                        &user.0,
+                        "Which card do you want to take a note on? \
+                         Give its index from the left (starting at 1), and then the note, \
+                         like `note 2 probably red` or `note @player 5 chop`.",
+                    );
+                    return Ok(());
+                }
+
+                let text = command.collect::<Vec<_>>().join(" ");
+                let game = self.games.get_mut(&game_id).unwrap();
+                if !game.note(&user.0, player, card.unwrap() - 1, &text) {
+                    msgs.send(
+                        &user.0,
+                        "There is no such card in that hand. \
+                         Remember that card indexing starts at 1.",
+                    );
+                    return Ok(());
+                }
+                if text.is_empty() {
+                    msgs.send(&user.0, "Note removed.");
+                } else {
+                    msgs.send(&user.0, "Noted.");
+                }
+                self.save().await.context("save after note")?;
+            }
             Some("clue") => {
                 let player = command.next();
                 let specifier = command.next();
@@ -508,37 +1166,17 @@ impl Hanabi {
 
                 let player = player.trim_start_matches("<@");
                 let player = player.trim_end_matches('>');
+                let Some(player) = self.games[&game_id].player_index(player) else {
+                    msgs.send(
+                        &user.0,
+                        &explain(&self.games[&game_id], ActionError::NoSuchPlayer),
+                    );
+                    return Ok(());
+                };
 
-                match self.games.get_mut(&game_id).unwrap().clue(player, clue) {
-                    Ok(_) => {}
-                    Err(hanabi::ClueError::NoSuchPlayer) => {
-                        msgs.send(
-                            &user.0,
-                            "The player you specified does not exist. \
-                             Remember to use Slack's @username tagging.",
-                        );
-                        return Ok(());
-                    }
-                    Err(hanabi::ClueError::NoMatchingCards) => {
-                        msgs.send(
-                            &user.0,
-                            "The card you specified is not in your hand. \
-                             Remember that card indexing starts at 1.",
-                        );
-                        return Ok(());
-                    }
-                    Err(hanabi::ClueError::NotEnoughClues) => {
-                        msgs.send(
-                            &user.0,
-                            "There are no clue tokens left, so you cannot clue.",
-                        );
-                        return Ok(());
-                    }
-                    Err(hanabi::ClueError::GameOver) => {}
-                }
-                self.progress_game(game_id, msgs)
+                self.take_action(game_id, user, Action::Clue { player, clue }, msgs)
                     .await
-                    .context("progress game after clue")?;
+                    .context("take clue action")?;
             }
             Some("play") => {
                 let card = command.next().and_then(|card| card.parse::<usize>().ok());
@@ -552,26 +1190,9 @@ impl Hanabi {
                     return Ok(());
                 }
 
-                match self
-                    .games
-                    .get_mut(&game_id)
-                    .unwrap()
-                    .play(card.unwrap() - 1)
-                {
-                    Ok(()) => {}
-                    Err(hanabi::PlayError::NoSuchCard) => {
-                        msgs.send(
-                            &user.0,
-                            "The card you specified is not in your hand. \
-                             Remember that card indexing starts at 1.",
-                        );
-                        return Ok(());
-                    }
-                    Err(hanabi::PlayError::GameOver) => {}
-                }
-                self.progress_game(game_id, msgs)
+                self.take_action(game_id, user, Action::Play(card.unwrap() - 1), msgs)
                     .await
-                    .context("progress game after play")?;
+                    .context("take play action")?;
             }
             Some("discard") => {
                 let card = command.next().and_then(|card| card.parse::<usize>().ok());
@@ -585,33 +1206,9 @@ impl Hanabi {
                     return Ok(());
                 }
 
-                match self
-                    .games
-                    .get_mut(&game_id)
-                    .unwrap()
-                    .discard(card.unwrap() - 1)
-                {
-                    Ok(()) => {}
-                    Err(hanabi::DiscardError::NoSuchCard) => {
-                        msgs.send(
-                            &user.0,
-                            "The card you specified is not in your hand. \
-                             Remember that card indexing starts at 1.",
-                        );
-                        return Ok(());
-                    }
-                    Err(hanabi::DiscardError::MaxClues) => {
-                        msgs.send(
-                            &user.0,
-                            "All 8 clue tokens are available, so discard is disallowed.",
-                        );
-                        return Ok(());
-                    }
-                    Err(hanabi::DiscardError::GameOver) => {}
-                }
-                self.progress_game(game_id, msgs)
+                self.take_action(game_id, user, Action::Discard(card.unwrap() - 1), msgs)
                     .await
-                    .context("progress game after discard")?;
+                    .context("take discard action")?;
             }
             Some(cmd) => {
                 msgs.send(
@@ -629,6 +1226,25 @@ impl Hanabi {
         Ok(())
     }
 
+    /// Have `user` take `action` in the game with id `game_id`, or tell them why they can't.
+    async fn take_action(
+        &mut self,
+        game_id: usize,
+        user: &SlackUserId,
+        action: Action,
+        msgs: &mut impl MessageProxy,
+    ) -> eyre::Result<()> {
+        let game = self.games.get_mut(&game_id).unwrap();
+        if let Err(e) = game.apply(action) {
+            msgs.send(&user.0, &explain(game, e));
+            return Ok(());
+        }
+
+        self.progress_game(game_id, msgs)
+            .await
+            .with_context(|| format!("progress game after {action:?}"))
+    }
+
     /// Called to progress the state of a game after a turn has been taken.
     ///
     /// This also detects if the game has ended, and if it has, returns the players of that game to
@@ -638,22 +1254,35 @@ impl Hanabi {
         game_id: usize,
         msgs: &mut impl MessageProxy,
     ) -> eyre::Result<()> {
-        let game = self.games.get_mut(&game_id).unwrap();
-        if game.progress_game(msgs) {
-            self.end_game(game_id, msgs);
-        } else if game.became_unwinnable() {
-            // last move caused game to be unwinnable -- call someone out
-            let game = self.games.get(&game_id).unwrap();
-            for p in game.players() {
-                msgs.send(
-                    p,
-                    &format!(
-                        "{} became unwinnable after {}",
-                        self.desc_game(game_id),
-                        game.last_move()
-                    ),
-                );
+        loop {
+            let game = self.games.get_mut(&game_id).unwrap();
+            let annotating = &self.annotating;
+            if game.progress_game(msgs, |p| annotating.contains(&SlackUserId(p.to_string()))) {
+                self.end_game(game_id, msgs);
+                break;
+            } else if let Some((max, reason)) = game.lowered_max_score() {
+                // last move lowered what the team can hope to score -- call someone out
+                let game = self.games.get(&game_id).unwrap();
+                for p in game.players() {
+                    msgs.send(
+                        p,
+                        &format!(
+                            "{}: max score is now {} ({}) after {}",
+                            self.desc_game(game_id),
+                            max,
+                            reason,
+                            game.last_move()
+                        ),
+                    );
+                }
             }
+
+            // bots take their turns right away
+            let game = self.games.get_mut(&game_id).unwrap();
+            if !is_bot(game.current_player()) {
+                break;
+            }
+            game.take_turn(&mut hanabi::strategy::Heuristic);
         }
 
         self.save().await
@@ -675,26 +1304,126 @@ impl Hanabi {
         format!("Game with {players}")
     }
 
+    /// The finished game with the given number in hanab.live's JSON format, if there is one.
+    pub fn export(&self, game_id: usize) -> eyre::Result<Option<String>> {
+        let Some(record) = self.finished.get(&game_id) else {
+            return Ok(None);
+        };
+        let game = hanabi::hanab_live::export(record)
+            .map_err(|e| eyre::eyre!("game #{game_id} cannot be replayed: {e:?}"))?;
+        Ok(Some(
+            serde_json::to_string(&game).context("serialize hanab.live game")?,
+        ))
+    }
+
+    /// Show `user` the turn they are at in the game they are reviewing.
+    fn show_review(&self, user: &SlackUserId, msgs: &mut impl MessageProxy) {
+        let (game_id, turn) = self.reviewing[user];
+        let record = &self.finished[&game_id];
+        let game = match record.replay(turn) {
+            Ok(game) => game,
+            Err(e) => {
+                eprintln!("game #{game_id} cannot be replayed to turn {turn}: {e:?}");
+                msgs.send(
+                    &user.0,
+                    &format!(
+                        "Sorry, I can't replay game #{game_id}, \
+                         since one of the moves in it isn't legal."
+                    ),
+                );
+                return;
+            }
+        };
+
+        if turn == 0 {
+            msgs.send(
+                &user.0,
+                &format!(
+                    "Game #{game_id} as it was dealt ({} turns in total). \
+                     Use `next`, `prev`, or `turn N` to move through the game.",
+                    record.turns()
+                ),
+            );
+        } else {
+            msgs.send(
+                &user.0,
+                &format!("Game #{game_id}, turn {turn} of {}:", record.turns()),
+            );
+        }
+        game.show_review(&user.0, msgs);
+    }
+
     /// Called to end a game.
     fn end_game(&mut self, game_id: usize, msgs: &mut impl MessageProxy) {
         // game has ended
         let desc = self.desc_game(game_id);
         let game = self.games.remove(&game_id).unwrap();
+        let mut missed = None;
+        let mut moments = Vec::new();
+        if let Some(record) = game.record() {
+            missed = hanabi::solver::missed(&record);
+            moments = hanabi::analysis::notable_moments(&record)
+                .unwrap_or_default()
+                .iter()
+                .map(|m| m.describe(&record.players))
+                .collect();
+            self.finished.insert(game_id, record);
+        }
+        if moments.len() > MAX_MOMENTS {
+            let more = moments.len() - MAX_MOMENTS;
+            moments.truncate(MAX_MOMENTS);
+            moments.push(format!(
+                "…and {more} more. Use `review {game_id}` to step through the game."
+            ));
+        }
+        self.learning.remove(&game_id);
+        if let Some(i) = self.puzzle_games.remove(&game_id) {
+            let best = self.puzzles.get(i).map_or(25, |puzzle| puzzle.best);
+            let verdict = if game.score() >= best {
+                "You solved it! :tada:".to_string()
+            } else {
+                format!("The best possible score was {best}, so there's still a way to do better.")
+            };
+            for p in game.players() {
+                msgs.send(p, &verdict);
+            }
+        }
 
-        println!("game #{} ended with score {}/25", game_id, game.score());
+        let unranked = if game.is_ranked() { "" } else { " (unranked)" };
+        println!(
+            "game #{} ended with score {}/25{unranked}",
+            game_id,
+            game.score()
+        );
         for p in game.players() {
             msgs.send(
                 p,
                 &format!(
-                    "{} ended with a score of {}/25 {}",
+                    "{} ended with a score of {}/25{unranked} {}",
                     desc,
                     game.score(),
                     game.score_smiley()
                 ),
             );
+            if let Some((turn, best)) = missed {
+                msgs.send(
+                    p,
+                    &format!(
+                        "With every card known, you could have gotten {best} from turn {turn}. \
+                         Use `review {game_id}` and then `turn {turn}` to take a look."
+                    ),
+                );
+            }
+            if !moments.is_empty() {
+                msgs.send(p, &format!("Notable moments:\n{}", moments.join("\n")));
+            }
         }
 
-        let mut players: Vec<_> = game.players().map(|s| SlackUserId(s.to_string())).collect();
+        let mut players: Vec<_> = game
+            .players()
+            .filter(|p| !is_bot(p))
+            .map(|s| SlackUserId(s.to_string()))
+            .collect();
 
         // shuffle players so we don't add them back to the queue in the same order they were in
         // when we started the game. if we don't do this, games would always have basically the
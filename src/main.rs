@@ -18,10 +18,14 @@ async fn main() -> eyre::Result<()> {
         .into();
     let api_token: SlackApiToken = SlackApiToken::new(api_token_value);
 
-    let hanabi = Hanabi::resume()
+    let mut hanabi = Hanabi::resume()
         .await
         .context("resume from saved game states")?
         .unwrap_or_default();
+    hanabi
+        .load_puzzles("puzzles.json")
+        .await
+        .context("load puzzles")?;
 
     let state = Arc::new(State {
         api_token,
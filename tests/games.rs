@@ -1,4 +1,12 @@
+use hanabot::hanabi::analysis::{self, Mistake, Moment};
+use hanabot::hanabi::builder::{BuildError, Builder};
+use hanabot::hanabi::puzzle::Puzzle;
+use hanabot::hanabi::strategy::{Cheater, Heuristic, Random, Reason, Strategy};
+use hanabot::hanabi::{hanab_live, inference, solver};
+use hanabot::hanabi::{Action, ActionError, Clue, Color, Effect, Game, GameOver, Number};
 use hanabot::{Hanabi, MessageProxy};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use slack_morphism::SlackUserId;
 use std::collections::HashMap;
 
@@ -132,12 +140,1318 @@ async fn start() {
     );
     assert_eq!(
         out.msgs["b"][1],
-        ":hourglass: It's <@a>'s turn; *8* :information_source: and 3 :bomb: remain."
+        ":hourglass: It's <@a>'s turn; *8* :information_source: and 3 :bomb: remain (pace 17)."
     );
     assert_eq!(out.msgs["b"].len(), 2);
     // TODO: actually assert about game startup
 }
 
+#[tokio::test]
+async fn card_possibilities() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    hanabi
+        .on_dm_recv("start", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+    out.msgs.clear();
+
+    // nothing is known about any card before any clues have been given
+    hanabi
+        .on_dm_recv("card 2", SlackUserId("b".to_string()), &mut out)
+        .await
+        .unwrap();
+    assert_eq!(
+        out.msgs["b"][0],
+        "From the clues you've been given, your card 2 could be any of:"
+    );
+    assert_eq!(out.msgs["b"].len(), 6);
+    assert_eq!(out.msgs["b"][1].matches("  |  ").count(), 4);
+    out.msgs.clear();
+
+    hanabi
+        .on_dm_recv("card 6", SlackUserId("b".to_string()), &mut out)
+        .await
+        .unwrap();
+    assert!(out.msgs["b"][0].starts_with("The card you specified is not in your hand."));
+}
+
+#[tokio::test]
+async fn remaining() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    hanabi
+        .on_dm_recv("start", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+    out.msgs.clear();
+
+    // b can see the five cards in a's hand, but none of the others
+    hanabi
+        .on_dm_recv("remaining", SlackUserId("b".to_string()), &mut out)
+        .await
+        .unwrap();
+    assert_eq!(
+        out.msgs["b"][0],
+        "These 45 cards are in the deck or in your hand:"
+    );
+    let listed: usize = out.msgs["b"][1..]
+        .iter()
+        .map(|line| line.matches(':').count() / 2 - 1)
+        .sum();
+    assert_eq!(listed, 45);
+}
+
+#[tokio::test]
+async fn learning_games() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+
+    // suggestions are only for learning games
+    for (u, cmd) in [("a", "start"), ("a", "suggest"), ("a", "quit")] {
+        hanabi
+            .on_dm_recv(cmd, SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    assert!(out.msgs["a"]
+        .iter()
+        .any(|m| m.starts_with("Suggestions are only available in learning games")));
+
+    out.msgs.clear();
+    for (u, cmd) in [("a", "start learning"), ("b", "suggest"), ("a", "suggest")] {
+        hanabi
+            .on_dm_recv(cmd, SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    assert!(out.msgs["b"]
+        .iter()
+        .any(|m| m.starts_with("This is a learning game")));
+    assert_eq!(
+        out.msgs["b"].last().unwrap(),
+        "It's not your turn yet, it's <@a>'s."
+    );
+    let suggestion = out.msgs["a"]
+        .iter()
+        .find(|m| m.starts_with("I'd "))
+        .unwrap();
+    assert!(suggestion.contains(", since "));
+}
+
+#[tokio::test]
+async fn open_hands() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    hanabi
+        .on_dm_recv("start open", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+
+    // a sees their own cards as well as b's
+    let in_hand = |msgs: &[String]| {
+        msgs.iter()
+            .flat_map(|m| m.lines())
+            .filter(|l| l.ends_with(" in hand"))
+            .count()
+    };
+    assert!(out.msgs["a"]
+        .iter()
+        .any(|m| m == "Your hand, which everyone can see in this game, is:"));
+    assert_eq!(in_hand(&out.msgs["a"]), 2);
+
+    hanabi
+        .on_dm_recv("quit", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+    assert!(out.msgs["b"]
+        .iter()
+        .any(|m| m.contains("ended with a score of 0/25 (unranked)")));
+
+    // games are normally closed and ranked
+    let game = Game::new(["a", "b"]);
+    assert!(game.is_ranked());
+    assert!(!game.with_open_hands().is_ranked());
+}
+
+#[tokio::test]
+async fn import_errors() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    let a = SlackUserId("a".to_string());
+    hanabi
+        .on_dm_recv("join", a.clone(), &mut out)
+        .await
+        .unwrap();
+    hanabi
+        .on_dm_recv(
+            r#"import {"deck": [{"suitIndex": 7, "rank": 1}]}"#,
+            a.clone(),
+            &mut out,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        out.msgs["a"].last().unwrap(),
+        "I can't replay that game: it uses suit number 7, and I only know the five standard suits."
+    );
+
+    // decks from other variants, or for a number of players we can't deal to, aren't played
+    let mut game = Game::with_rng(["x", "y", "z"], &mut StdRng::seed_from_u64(2));
+    let mut strategies: Vec<Box<dyn Strategy>> =
+        vec![Box::new(Cheater), Box::new(Cheater), Box::new(Cheater)];
+    game.play_out(&mut strategies);
+    let json = hanab_live::export(&game.record().unwrap()).unwrap();
+    let mut variant = json.clone();
+    variant.options.variant = "Rainbow (6 Suits)".to_string();
+    let mut six = json.clone();
+    six.players = ["1", "2", "3", "4", "5", "6"].map(String::from).to_vec();
+    for (json, reply) in [
+        (
+            variant,
+            r#"I can't play with that deck: it's played with a variant, and I only know the rules of "No Variant"."#,
+        ),
+        (
+            six,
+            "I can't play with that deck: it has 6 players, and games need between 2 and 5.",
+        ),
+        (
+            json,
+            "That deck was dealt to 3 players, \
+             so I can only deal it once 3 players, including you, are waiting for a game.",
+        ),
+    ] {
+        let cmd = format!("start {}", serde_json::to_string(&json).unwrap());
+        hanabi.on_dm_recv(&cmd, a.clone(), &mut out).await.unwrap();
+        assert_eq!(out.msgs["a"].last().unwrap(), reply);
+    }
+    assert!(Game::with_deck_order(["1"; 6], game.record().unwrap().deck).is_none());
+}
+
+#[tokio::test]
+async fn annotations() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    hanabi
+        .on_dm_recv("annotate", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+    hanabi
+        .on_dm_recv("start", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+
+    // nothing has been discarded or played yet, so only fives are critical, and nothing is trash
+    let in_hand = |msgs: &[String]| {
+        msgs.iter()
+            .flat_map(|m| m.lines())
+            .find(|l| l.ends_with(" in hand"))
+            .map(String::from)
+    };
+    let seen_by_a = in_hand(&out.msgs["a"]).unwrap();
+    assert_eq!(
+        seen_by_a.matches(":rotating_light:").count(),
+        seen_by_a.matches(":five:").count()
+    );
+    assert!(!seen_by_a.contains(":wastebasket:"));
+
+    // b didn't ask for annotations, and a can turn them off again
+    out.msgs.clear();
+    hanabi
+        .on_dm_recv("hands", SlackUserId("b".to_string()), &mut out)
+        .await
+        .unwrap();
+    assert!(!in_hand(&out.msgs["b"])
+        .unwrap()
+        .contains(":rotating_light:"));
+    hanabi
+        .on_dm_recv("annotate off", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+    hanabi
+        .on_dm_recv("hands", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+    assert!(!in_hand(&out.msgs["a"])
+        .unwrap()
+        .contains(":rotating_light:"));
+}
+
+#[tokio::test]
+async fn clue_history() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    hanabi
+        .on_dm_recv("start", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+
+    // only the first clue that touches one of b's cards will be given
+    for number in ["one", "two", "three", "four", "five"] {
+        hanabi
+            .on_dm_recv(
+                &format!("clue <@b> {number}"),
+                SlackUserId("a".to_string()),
+                &mut out,
+            )
+            .await
+            .unwrap();
+    }
+    out.msgs.clear();
+    hanabi
+        .on_dm_recv("clues", SlackUserId("b".to_string()), &mut out)
+        .await
+        .unwrap();
+
+    let msgs = &out.msgs["b"];
+    assert_eq!(msgs[0], "Clues given to your cards:");
+    assert!(msgs[1..6].iter().all(|m| m.ends_with(" from <@a>")));
+    assert!(msgs[1..6].iter().any(|m| !m.contains("not ")));
+    assert_eq!(msgs[6], "Clues given to <@a>'s cards:");
+    assert!(msgs[7..].iter().all(|m| m.ends_with(": no clues")));
+}
+
+#[tokio::test]
+async fn notes_follow_cards() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    for cmd in [
+        "start",
+        "note <@b> 1 played next",
+        "note <@b> 3 keep this one",
+    ] {
+        hanabi
+            .on_dm_recv(cmd, SlackUserId("a".to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    for number in ["one", "two", "three", "four", "five"] {
+        hanabi
+            .on_dm_recv(
+                &format!("clue <@b> {number}"),
+                SlackUserId("a".to_string()),
+                &mut out,
+            )
+            .await
+            .unwrap();
+    }
+    hanabi
+        .on_dm_recv("play 1", SlackUserId("b".to_string()), &mut out)
+        .await
+        .unwrap();
+
+    // the played card's note is gone, and the other note moved one slot to the left
+    out.msgs.clear();
+    hanabi
+        .on_dm_recv("hands", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+    let known = out.msgs["a"]
+        .iter()
+        .flat_map(|m| m.lines())
+        .filter(|l| l.ends_with(" known"))
+        .nth(1)
+        .unwrap();
+    let notes: Vec<_> = known
+        .split("  |  ")
+        .map(|card| card.split(" :memo: ").nth(1))
+        .collect();
+    assert_eq!(notes[1], Some("keep this one"));
+    assert_eq!(notes.iter().flatten().count(), 1);
+
+    // and nobody else can see it
+    out.msgs.clear();
+    hanabi
+        .on_dm_recv("hands", SlackUserId("b".to_string()), &mut out)
+        .await
+        .unwrap();
+    assert!(out.msgs["b"].iter().all(|m| !m.contains(":memo:")));
+}
+
+#[tokio::test]
+async fn reveal_at_end() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    hanabi
+        .on_dm_recv("start", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+
+    // blindly playing cards will end the game long before the deck runs out
+    for _ in 0..40 {
+        for u in ["a", "b"] {
+            hanabi
+                .on_dm_recv("play 1", SlackUserId(u.to_string()), &mut out)
+                .await
+                .unwrap();
+        }
+    }
+
+    for u in ["a", "b"] {
+        let msgs = &out.msgs[u];
+        let reveal = msgs
+            .iter()
+            .position(|m| m.starts_with("The hands at the end were:"))
+            .unwrap();
+        assert_eq!(msgs[reveal].lines().count(), 3);
+        assert!(msgs[reveal].contains("<@a>: ") && msgs[reveal].contains("<@b>: "));
+        assert!(msgs[reveal + 1].starts_with("The deck still held"));
+    }
+}
+
+#[tokio::test]
+async fn review() {
+    let mut hanabi = Hanabi::default();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    // only the first clue that touches one of b's cards will be given
+    for cmd in [
+        "start",
+        "review 0",
+        "clue <@b> one",
+        "clue <@b> two",
+        "clue <@b> three",
+        "clue <@b> four",
+        "clue <@b> five",
+        "quit",
+    ] {
+        hanabi
+            .on_dm_recv(cmd, SlackUserId("a".to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    out.msgs.clear();
+
+    // the game that was quit can now be reviewed, one turn at a time
+    for cmd in ["next", "next", "prev", "turn 0"] {
+        hanabi
+            .on_dm_recv(cmd, SlackUserId("b".to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    hanabi
+        .on_dm_recv("review 0", SlackUserId("b".to_string()), &mut out)
+        .await
+        .unwrap();
+    hanabi
+        .on_dm_recv("next", SlackUserId("b".to_string()), &mut out)
+        .await
+        .unwrap();
+    let headers: Vec<_> = out.msgs["b"]
+        .iter()
+        .filter(|m| m.starts_with("Game #0"))
+        .collect();
+    assert_eq!(headers.len(), 2);
+    assert!(headers[0].starts_with("Game #0 as it was dealt (1 turns in total)."));
+    assert_eq!(headers[1], "Game #0, turn 1 of 1:");
+
+    // every reviewed turn shows the discard pile too
+    let discards = out.msgs["b"]
+        .iter()
+        .filter(|m| *m == "The discard pile is empty.")
+        .count();
+    assert_eq!(discards, 2);
+}
+
+#[tokio::test]
+async fn puzzle() {
+    let mut hanabi = Hanabi::default();
+    hanabi.load_puzzles("puzzles.json").await.unwrap();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+
+    // the first puzzle is solved by getting b to discard so that a can play before b's last turn
+    for (u, cmd) in [
+        ("a", "puzzle 1 <@b>"),
+        ("a", "clue <@b> red"),
+        ("b", "discard 1"),
+        ("a", "play 1"),
+        ("b", "play 1"),
+    ] {
+        hanabi
+            .on_dm_recv(cmd, SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    for u in ["a", "b"] {
+        assert!(out.msgs[u].iter().any(|m| m == "You solved it! :tada:"));
+    }
+}
+
+#[tokio::test]
+async fn puzzle_alone() {
+    let mut hanabi = Hanabi::default();
+    hanabi.load_puzzles("puzzles.json").await.unwrap();
+    let mut out = DummyMessageProxy::default();
+
+    // a bot takes the empty seat, and moves as soon as it's its turn
+    for cmd in ["join", "puzzle 1", "clue bot2 red", "play 1"] {
+        hanabi
+            .on_dm_recv(cmd, SlackUserId("a".to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    assert!(out.msgs["a"]
+        .iter()
+        .any(|m| m == "I'll play `bot2`, using what that seat is allowed to know."));
+    assert!(out.msgs["a"].iter().any(|m| m == "You solved it! :tada:"));
+    assert_eq!(out.msgs.len(), 1, "the bot was sent messages");
+
+    // and the human can play again afterwards, while the bot is gone
+    out.msgs.clear();
+    hanabi
+        .on_dm_recv("players", SlackUserId("a".to_string()), &mut out)
+        .await
+        .unwrap();
+    assert!(out.msgs["a"][0].ends_with("Waiting: <@a>"));
+}
+
+#[tokio::test]
+async fn puzzles_must_have_the_right_best() {
+    let mut puzzles: Vec<Puzzle> =
+        serde_json::from_str(&std::fs::read_to_string("puzzles.json").unwrap()).unwrap();
+    puzzles[0].best -= 1;
+    let path = std::env::temp_dir().join("hanabot-wrong-puzzles.json");
+    std::fs::write(&path, serde_json::to_vec(&puzzles).unwrap()).unwrap();
+
+    let mut hanabi = Hanabi::default();
+    let err = hanabi
+        .load_puzzles(path.to_str().unwrap())
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("says the best score is 24, but it is 25"));
+
+    // puzzles are endgames, so the solver doesn't have to search through long decks
+    puzzles[0].best += 1;
+    puzzles[1].deck = ["r4", "g3", "y1", "y1", "g1", "w1"]
+        .map(String::from)
+        .to_vec();
+    std::fs::write(&path, serde_json::to_vec(&puzzles).unwrap()).unwrap();
+    let err = hanabi
+        .load_puzzles(path.to_str().unwrap())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("has 6 cards in the deck"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn puzzle_clue_history() {
+    let mut hanabi = Hanabi::default();
+    hanabi.load_puzzles("puzzles.json").await.unwrap();
+    let mut out = DummyMessageProxy::default();
+    for u in ["a", "b", "c"] {
+        hanabi
+            .on_dm_recv("join", SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+
+    // in the second puzzle, c goes first, and a was told about their five before it started
+    for (u, cmd) in [
+        ("a", "puzzle 2 <@b> <@c>"),
+        ("c", "discard 1"),
+        ("a", "clue <@b> four"),
+        ("b", "clues"),
+    ] {
+        hanabi
+            .on_dm_recv(cmd, SlackUserId(u.to_string()), &mut out)
+            .await
+            .unwrap();
+    }
+    let lines: Vec<_> = out.msgs["b"].iter().flat_map(|m| m.lines()).collect();
+    let yours = lines
+        .iter()
+        .position(|&l| l == "Clues given to your cards:")
+        .unwrap();
+    assert_eq!(lines[yours + 2], "2: :four: from <@a>");
+    let as_cards = lines
+        .iter()
+        .position(|&l| l == "Clues given to <@a>'s cards:")
+        .unwrap();
+    assert_eq!(lines[as_cards + 2], "2: :five: before play began");
+}
+
+#[test]
+fn strategies_play_out() {
+    for players in 2..=5 {
+        let names: Vec<_> = (0..players).map(|p| p.to_string()).collect();
+
+        let mut game = Game::new(names.iter().map(|s| &**s));
+        let mut strategies: Vec<Box<dyn Strategy>> = (0..players)
+            .map(|p| Box::new(Random::new(StdRng::seed_from_u64(p))) as Box<dyn Strategy>)
+            .collect();
+        let score = game.play_out(&mut strategies);
+        assert!(game.is_over());
+        assert_eq!(score, game.score());
+
+        let mut game = Game::new(names.iter().map(|s| &**s));
+        let mut strategies: Vec<Box<dyn Strategy>> = (0..players)
+            .map(|_| Box::new(Cheater) as Box<dyn Strategy>)
+            .collect();
+        game.play_out(&mut strategies);
+        assert!(game.is_over());
+
+        let mut game = Game::new(names.iter().map(|s| &**s));
+        let mut strategies: Vec<Box<dyn Strategy>> = (0..players)
+            .map(|_| Box::new(Heuristic) as Box<dyn Strategy>)
+            .collect();
+        game.play_out(&mut strategies);
+        assert!(game.is_over());
+    }
+}
+
+#[test]
+fn heuristic_suggestions() {
+    use Color::*;
+    use Number::*;
+
+    // a knows their first card is the red one, and b has a one nobody has told them about
+    let game = Game::builder(["a", "b"])
+        .hand(0, [(Red, One), (Blue, Four)])
+        .hand(1, [(Green, Three), (Yellow, One)])
+        .deck([(White, One), (White, Two)])
+        .clued(0, Clue::Color(Red))
+        .clued(0, Clue::Number(One))
+        .clues(1)
+        .build()
+        .unwrap();
+    let suggestions = Heuristic::suggestions(&game.observe(0));
+    assert_eq!(suggestions[0].action, Action::Play(0));
+    assert_eq!(suggestions[0].reason, Reason::MayBePlayable(1.0));
+    assert_eq!(suggestions.len(), game.legal_actions().len());
+    assert!(suggestions.windows(2).all(|w| w[0].value >= w[1].value));
+
+    // b doesn't know anything, so the clue about the yellow one is the one to give
+    let clue = suggestions
+        .iter()
+        .find(|s| matches!(s.action, Action::Clue { .. }))
+        .unwrap();
+    assert_eq!(
+        clue.action,
+        Action::Clue {
+            player: 1,
+            clue: Clue::Number(One)
+        }
+    );
+    assert_eq!(clue.reason, Reason::Playable(vec![(Yellow, One)]));
+}
+
+#[test]
+fn legal_actions_validate() {
+    let mut game = Game::with_rng(["a", "b", "c"], &mut StdRng::seed_from_u64(42));
+
+    // with all 8 clue tokens, nobody can discard
+    assert_eq!(
+        game.validate(&Action::Discard(0)),
+        Err(ActionError::MaxClues)
+    );
+    assert_eq!(
+        game.validate(&Action::Play(5)),
+        Err(ActionError::NoSuchCard)
+    );
+    let clue = Clue::Number(Number::One);
+    assert_eq!(
+        game.validate(&Action::Clue { player: 0, clue }),
+        Err(ActionError::ClueToSelf)
+    );
+    assert_eq!(
+        game.validate(&Action::Clue { player: 3, clue }),
+        Err(ActionError::NoSuchPlayer)
+    );
+
+    let mut strategy = Random::new(StdRng::seed_from_u64(0));
+    while !game.is_over() {
+        let legal = game.legal_actions();
+        assert!(!legal.is_empty());
+        for action in &legal {
+            assert_eq!(game.validate(action), Ok(()), "{action:?}");
+        }
+
+        // every clue that isn't listed must be rejected
+        let colors = [
+            Color::Red,
+            Color::Green,
+            Color::White,
+            Color::Blue,
+            Color::Yellow,
+        ];
+        let numbers = [
+            Number::One,
+            Number::Two,
+            Number::Three,
+            Number::Four,
+            Number::Five,
+        ];
+        let clues = colors
+            .into_iter()
+            .map(Clue::Color)
+            .chain(numbers.into_iter().map(Clue::Number));
+        for clue in clues {
+            for player in 0..3 {
+                let action = Action::Clue { player, clue };
+                assert_eq!(
+                    legal.contains(&action),
+                    game.validate(&action).is_ok(),
+                    "{action:?}"
+                );
+            }
+        }
+
+        game.take_turn(&mut strategy);
+    }
+    assert!(game.legal_actions().is_empty());
+    assert_eq!(game.validate(&Action::Play(0)), Err(ActionError::GameOver));
+}
+
+#[test]
+fn apply_outcomes() {
+    let mut game = Game::with_rng(["a", "b"], &mut StdRng::seed_from_u64(7));
+
+    // an illegal action leaves the game untouched
+    assert_eq!(game.apply(Action::Discard(0)), Err(ActionError::MaxClues));
+
+    let clue = game
+        .legal_actions()
+        .into_iter()
+        .find_map(|action| match action {
+            Action::Clue { clue, .. } => Some(clue),
+            _ => None,
+        });
+    let outcome = game
+        .apply(Action::Clue {
+            player: 1,
+            clue: clue.unwrap(),
+        })
+        .unwrap();
+    assert_eq!(outcome.player, 0);
+    assert!(
+        matches!(outcome.effect, Effect::Clued { player: 1, touched, .. } if !touched.is_empty())
+    );
+    assert_eq!(outcome.drew, None);
+    assert!(!outcome.clue_refunded);
+
+    let outcome = game.apply(Action::Discard(0)).unwrap();
+    assert_eq!(outcome.player, 1);
+    assert!(matches!(outcome.effect, Effect::Discarded { .. }));
+    assert!(outcome.drew.is_some());
+    assert!(outcome.clue_refunded);
+    assert_eq!(outcome.game_over, None);
+
+    // keep playing the first card until the team runs out of lives or turns
+    let mut last = None;
+    let mut started_last_round = false;
+    while !game.is_over() {
+        let outcome = game.apply(Action::Play(0)).unwrap();
+        started_last_round |= outcome.last_round_started;
+        last = Some(outcome);
+    }
+    match last.unwrap().game_over {
+        Some(GameOver::OutOfLives) => assert_eq!(game.lives(), 0),
+        Some(GameOver::LastRoundOver) => assert!(started_last_round),
+        over => panic!("unexpected end of game {over:?}"),
+    }
+}
+
+#[test]
+fn clue_touches_slots() {
+    // every possible clue should describe exactly the slots it touched
+    let game = Game::with_rng(["a", "b", "c"], &mut StdRng::seed_from_u64(3));
+    for action in game.legal_actions() {
+        if !matches!(action, Action::Clue { .. }) {
+            continue;
+        }
+        let mut game = Game::with_rng(["a", "b", "c"], &mut StdRng::seed_from_u64(3));
+        let Effect::Clued { touched, .. } = game.apply(action).unwrap().effect else {
+            unreachable!("a clue should have been given");
+        };
+        let slots: Vec<_> = touched.iter().map(|slot| (slot + 1).to_string()).collect();
+        let described = touched.to_string();
+        match &*slots {
+            [slot] => assert_eq!(described, format!("slot {slot}")),
+            [.., last] => {
+                assert!(described.starts_with("slots "));
+                assert!(described.ends_with(&format!(" and {last}")));
+                assert!(slots.iter().all(|slot| described.contains(&**slot)));
+            }
+            [] => unreachable!("clues must touch at least one card"),
+        }
+    }
+}
+
+#[test]
+fn card_ids_are_stable() {
+    let mut game = Game::with_rng(["a", "b"], &mut StdRng::seed_from_u64(11));
+
+    // cards are numbered in the order they were dealt
+    let ids = |game: &Game, player| {
+        let view = game.observe(0);
+        (0..view.hand_size(player))
+            .map(|slot| view.card_id(player, slot).unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&game, 0), [0, 1, 2, 3, 4]);
+    assert_eq!(ids(&game, 1), [5, 6, 7, 8, 9]);
+
+    // a clue lets b discard; the remaining cards shift left, but keep their ids
+    let clue = game
+        .legal_actions()
+        .into_iter()
+        .find(|action| matches!(action, Action::Clue { .. }))
+        .unwrap();
+    game.apply(clue).unwrap();
+    game.apply(Action::Discard(1)).unwrap();
+    assert_eq!(ids(&game, 1), [5, 7, 8, 9, 10]);
+}
+
+#[test]
+fn records_replay() {
+    let mut game = Game::with_rng(["a", "b", "c"], &mut StdRng::seed_from_u64(5));
+    let mut strategies: Vec<Box<dyn Strategy>> =
+        vec![Box::new(Cheater), Box::new(Cheater), Box::new(Cheater)];
+    let score = game.play_out(&mut strategies);
+
+    let record = game.record().unwrap();
+    assert_eq!(record.players, ["a", "b", "c"]);
+    assert_eq!(record.deck.len(), 50);
+
+    let replayed = record.replay(record.turns()).unwrap();
+    assert_eq!(replayed.score(), score);
+    assert!(replayed.is_over());
+    assert_eq!(replayed.record().unwrap(), record);
+
+    // replaying part of the game gives the game as it was then
+    let halfway = record.replay(record.turns() / 2).unwrap();
+    assert_eq!(
+        halfway.record().unwrap().actions,
+        record.actions[..record.turns() / 2]
+    );
+    assert!(!halfway.is_over());
+}
+
+#[test]
+fn hanab_live_export() {
+    let mut game = Game::with_rng(["a", "b"], &mut StdRng::seed_from_u64(9));
+    let mut strategies: Vec<Box<dyn Strategy>> = vec![Box::new(Cheater), Box::new(Cheater)];
+    game.play_out(&mut strategies);
+
+    let record = game.record().unwrap();
+    let json = hanab_live::export(&record).unwrap();
+    assert_eq!(json.players, ["a", "b"]);
+    assert_eq!(json.deck.len(), 50);
+    assert_eq!(json.actions.len(), record.turns());
+
+    // plays and discards refer to cards by their position in the deck
+    let mut played = std::collections::HashSet::new();
+    for (action, json) in record.actions.iter().zip(&json.actions) {
+        match action {
+            Action::Play(_) => assert_eq!(json.kind, hanab_live::ACTION_PLAY),
+            Action::Discard(_) => assert_eq!(json.kind, hanab_live::ACTION_DISCARD),
+            Action::Clue { player, .. } => {
+                assert_eq!(json.target, *player);
+                continue;
+            }
+        }
+        assert!(json.target < 50);
+        assert!(played.insert(json.target), "a card left the hand twice");
+    }
+}
+
+#[test]
+fn hanab_live_import() {
+    let mut game = Game::with_rng(["a", "b", "c"], &mut StdRng::seed_from_u64(4));
+    let mut strategies: Vec<Box<dyn Strategy>> =
+        vec![Box::new(Cheater), Box::new(Cheater), Box::new(Cheater)];
+    game.play_out(&mut strategies);
+
+    // exporting and importing a game gets back the same game
+    let record = game.record().unwrap();
+    let mut json = hanab_live::export(&record).unwrap();
+    assert_eq!(hanab_live::import(&json), Ok(record.clone()));
+
+    // hanab.live marks the end of the game with an extra action, which we don't need
+    json.actions.push(hanab_live::ActionJson {
+        kind: hanab_live::ACTION_GAME_OVER,
+        target: 0,
+        value: 0,
+    });
+    assert_eq!(hanab_live::import(&json), Ok(record));
+
+    // games that can't have happened are rejected
+    let mut bad = json.clone();
+    bad.deck.pop();
+    assert_eq!(
+        hanab_live::import(&bad),
+        Err(hanab_live::ImportError::NotAFullDeck)
+    );
+    let mut bad = json.clone();
+    bad.deck[0].rank = 6;
+    assert_eq!(
+        hanab_live::import(&bad),
+        Err(hanab_live::ImportError::UnknownRank(6))
+    );
+    let mut bad = json.clone();
+    bad.actions[0] = hanab_live::ActionJson {
+        kind: hanab_live::ACTION_PLAY,
+        target: 49,
+        value: 0,
+    };
+    assert_eq!(
+        hanab_live::import(&bad),
+        Err(hanab_live::ImportError::CardNotInHand(0))
+    );
+}
+
+#[test]
+fn built_positions() {
+    use Color::*;
+    use Number::*;
+
+    // every card that isn't placed anywhere counts as discarded, so red can only get to four, and
+    // blue and green to one. that would be six, but with one card in the deck and two players,
+    // there are only turns left for three more plays.
+    let game = Game::builder(["a", "b"])
+        .hand(0, [(Red, Four), (Blue, One)])
+        .hand(1, [(Red, Three)])
+        .deck([(Green, One)])
+        .played(Red, Two)
+        .build()
+        .unwrap();
+    assert_eq!(game.score(), 2);
+    assert_eq!(game.max_score(), 5);
+    assert_eq!(game.pace(), -1);
+    let view = game.observe(1);
+    assert_eq!(view.card(0, 1), Some((Blue, One)));
+    assert_eq!(view.discarded(Red, Five), 1);
+    assert_eq!(view.discarded(Green, One), 2);
+
+    // clues set up what the players know without using up tokens
+    let game = Game::builder(["a", "b"])
+        .hand(1, [(Red, One), (Blue, One)])
+        .clued(1, Clue::Color(Red))
+        .clues(3)
+        .build()
+        .unwrap();
+    let view = game.observe(0);
+    assert_eq!(view.clue_tokens(), 3);
+    assert_eq!(view.knowledge(1, 0).unwrap().color(), Some(Red));
+    assert!(!view.knowledge(1, 1).unwrap().may_be_color(Red));
+
+    // positions that can't happen are rejected
+    let err = |builder: Builder| builder.build().err().unwrap();
+    assert_eq!(err(Game::builder(["a"])), BuildError::Players(1));
+    assert_eq!(
+        err(Game::builder(["a", "b"]).hand(2, [])),
+        BuildError::NoSuchPlayer(2)
+    );
+    assert_eq!(
+        err(Game::builder(["a", "b"])
+            .played(Red, Two)
+            .hand(0, [(Red, Two), (Red, Two)])),
+        BuildError::TooManyCopies(Red, Two)
+    );
+    assert_eq!(
+        err(Game::builder(["a", "b", "c", "d"]).hand(3, [(Red, One); 5])),
+        BuildError::HandTooBig(3)
+    );
+    assert_eq!(
+        err(Game::builder(["a", "b"]).clues(9)),
+        BuildError::TooManyClues
+    );
+}
+
+#[test]
+fn finishing_a_stack_refunds_a_clue() {
+    use Color::*;
+    use Number::*;
+
+    let position = |clues| {
+        Game::builder(["a", "b"])
+            .hand(0, [(Red, Five), (Blue, Five)])
+            .hand(1, [(Green, One)])
+            .deck([(White, One), (White, Two)])
+            .played(Red, Four)
+            .clues(clues)
+            .build()
+            .unwrap()
+    };
+
+    let mut game = position(3);
+    let outcome = game.apply(Action::Play(0)).unwrap();
+    assert_eq!(outcome.effect, Effect::Played { card: (Red, Five) });
+    assert!(outcome.clue_refunded);
+    assert_eq!(outcome.drew, Some((White, One)));
+    assert_eq!(game.observe(0).clue_tokens(), 4);
+
+    // but there can never be more than 8
+    let mut game = position(8);
+    let outcome = game.apply(Action::Play(0)).unwrap();
+    assert!(!outcome.clue_refunded);
+    assert_eq!(game.observe(0).clue_tokens(), 8);
+}
+
+#[test]
+fn misplays_cost_lives() {
+    use Color::*;
+    use Number::*;
+
+    let mut game = Game::builder(["a", "b"])
+        .hand(0, [(Red, Three), (Blue, One)])
+        .hand(1, [(Red, Four)])
+        .deck([(White, One), (White, Two), (White, Three)])
+        .lives(2)
+        .build()
+        .unwrap();
+
+    let outcome = game.apply(Action::Play(0)).unwrap();
+    assert_eq!(outcome.effect, Effect::Misplayed { card: (Red, Three) });
+    assert_eq!(game.lives(), 1);
+    assert_eq!(game.observe(0).discarded(Red, Three), 2);
+    assert_eq!(outcome.game_over, None);
+
+    let outcome = game.apply(Action::Play(0)).unwrap();
+    assert_eq!(outcome.game_over, Some(GameOver::OutOfLives));
+    assert_eq!(game.lives(), 0);
+    assert!(game.is_over());
+    assert_eq!(game.apply(Action::Play(0)), Err(ActionError::GameOver));
+}
+
+#[test]
+fn last_round() {
+    use Color::*;
+    use Number::*;
+
+    let mut game = Game::builder(["a", "b", "c"])
+        .hand(0, [(Red, One)])
+        .hand(1, [(Red, Two)])
+        .hand(2, [(Red, Three)])
+        .deck([(Red, Four)])
+        .build()
+        .unwrap();
+
+    // drawing the last card starts the last round, in which everyone gets one more turn
+    let outcome = game.apply(Action::Play(0)).unwrap();
+    assert!(outcome.last_round_started);
+    assert_eq!(game.max_score(), 4);
+    for slot in [0, 0] {
+        assert_eq!(game.apply(Action::Play(slot)).unwrap().game_over, None);
+    }
+    let outcome = game.apply(Action::Play(0)).unwrap();
+    assert_eq!(outcome.effect, Effect::Played { card: (Red, Four) });
+    assert_eq!(outcome.game_over, Some(GameOver::LastRoundOver));
+    assert_eq!(game.score(), 4);
+}
+
+#[test]
+fn solver_agrees_with_puzzles() {
+    let puzzles: Vec<Puzzle> =
+        serde_json::from_str(&std::fs::read_to_string("puzzles.json").unwrap()).unwrap();
+    for puzzle in puzzles {
+        let names: Vec<_> = (0..puzzle.players()).map(|p| p.to_string()).collect();
+        let game = puzzle.build(names.iter().map(|s| &**s)).unwrap();
+        assert_eq!(solver::best_score(&game), puzzle.best, "{}", puzzle.name);
+    }
+}
+
+#[test]
+fn solver_finds_missed_points() {
+    let mut game = Game::with_rng(["a", "b", "c"], &mut StdRng::seed_from_u64(5));
+    while !game.observe(0).is_last_round() {
+        game.take_turn(&mut Cheater);
+    }
+    let last_round = game.record().unwrap().turns();
+    let best = solver::best_score(&game);
+    assert!(best >= game.score());
+    assert!(best <= game.max_score());
+
+    // throw away the last round
+    while !game.is_over() {
+        let action = game
+            .legal_actions()
+            .into_iter()
+            .find(|a| !matches!(a, Action::Play(_)))
+            .unwrap();
+        game.apply(action).unwrap();
+    }
+    assert_eq!(solver::best_score(&game), game.score());
+
+    let record = game.record().unwrap();
+    let (turn, could) = solver::missed(&record).unwrap();
+    assert!(turn >= last_round);
+    assert!(could > game.score());
+    assert_eq!(solver::best_score(&record.replay(turn).unwrap()), could);
+    for later in turn + 1..=record.turns() {
+        let then = record.replay(later).unwrap();
+        assert_eq!(solver::best_score(&then), game.score());
+    }
+}
+
+#[test]
+fn notable_moments() {
+    use Color::*;
+    use Number::*;
+
+    // a and b's hands, then the first cards they'll draw, then the rest of the deck
+    let mut deck = vec![
+        (Red, One),
+        (Red, Four),
+        (Blue, One),
+        (Green, One),
+        (White, Five),
+        (Red, Two),
+        (Yellow, One),
+        (Yellow, Two),
+        (Blue, Three),
+        (Green, One),
+        (White, One),
+        (White, One),
+        (White, One),
+        (White, Two),
+        (White, Two),
+        (White, Three),
+    ];
+    for color in [Red, Yellow, Green, Blue, White] {
+        for number in [One, Two, Three, Four, Five] {
+            let dealt = deck.iter().filter(|&&c| c == (color, number)).count();
+            deck.extend(std::iter::repeat_n(
+                (color, number),
+                number.copies() - dealt,
+            ));
+        }
+    }
+
+    let mut game = Game::with_deck_order(["a", "b"], deck).unwrap();
+    for action in [
+        Action::Clue {
+            player: 1,
+            clue: Clue::Color(Red),
+        },
+        Action::Clue {
+            player: 0,
+            clue: Clue::Color(Red),
+        },
+        // b already knows which card is red
+        Action::Clue {
+            player: 1,
+            clue: Clue::Color(Red),
+        },
+        // the red two that was just clued
+        Action::Discard(0),
+        Action::Play(3),
+        // the yellow two
+        Action::Play(1),
+        // the second green one, which can't be played anymore
+        Action::Clue {
+            player: 1,
+            clue: Clue::Color(Green),
+        },
+        // the yellow one
+        Action::Discard(0),
+        // the red four that was clued with the red one
+        Action::Play(1),
+        Action::Clue {
+            player: 0,
+            clue: Clue::Color(Blue),
+        },
+        // the only white five
+        Action::Discard(2),
+    ] {
+        game.apply(action).unwrap();
+    }
+
+    let record = game.record().unwrap();
+    let moments = analysis::notable_moments(&record).unwrap();
+    let moment = |turn, player, mistake| Moment {
+        turn,
+        player,
+        mistake,
+    };
+    assert_eq!(
+        moments,
+        [
+            moment(
+                3,
+                0,
+                Mistake::Redundant {
+                    player: 1,
+                    clue: Clue::Color(Red)
+                }
+            ),
+            moment(4, 1, Mistake::DiscardedClued((Red, Two))),
+            moment(6, 1, Mistake::BlindMisplayed((Yellow, Two))),
+            moment(
+                7,
+                0,
+                Mistake::TouchedTrash {
+                    player: 1,
+                    card: (Green, One)
+                }
+            ),
+            moment(8, 1, Mistake::DiscardedPlayable((Yellow, One))),
+            moment(9, 0, Mistake::Misplayed((Red, Four))),
+            moment(11, 0, Mistake::DiscardedCritical((White, Five))),
+        ]
+    );
+    assert_eq!(
+        moments[0].describe(&record.players),
+        "Turn 3: <@a> clued <@b> :heart:, which told them nothing new."
+    );
+}
+
+#[test]
+fn inference_is_consistent() {
+    let mut game = Game::with_rng(["a", "b", "c", "d"], &mut StdRng::seed_from_u64(3));
+    let mut strategy = Random::new(StdRng::seed_from_u64(3));
+    while !game.is_over() {
+        for player in 0..4 {
+            let view = game.observe_all(player);
+            let unseen = inference::Unseen::new(&view);
+            for (slot, dist) in inference::hand(&view).iter().enumerate() {
+                let total: f64 = dist.likeliest().iter().map(|&(_, p)| p).sum();
+                assert!((total - 1.0).abs() < 1e-9, "{total}");
+
+                // the card's real identity must always be considered possible
+                let (c, n) = view.peek(slot);
+                assert!(dist.get(c, n) > 0.0);
+                assert!(unseen.get(c, n) > 0);
+            }
+        }
+        game.take_turn(&mut strategy);
+    }
+}
+
+#[test]
+fn max_score_only_drops() {
+    for seed in 0..20 {
+        let mut game = Game::with_rng(["a", "b", "c"], &mut StdRng::seed_from_u64(seed));
+        let mut strategy = Random::new(StdRng::seed_from_u64(seed));
+        let mut maxes = vec![game.max_score()];
+        assert_eq!(maxes[0], 25);
+        while !game.is_over() {
+            game.take_turn(&mut strategy);
+            let max = game.max_score();
+            assert!(max <= *maxes.last().unwrap());
+            assert!(max >= game.score());
+            if max < *maxes.last().unwrap() {
+                let (lowered, _) = game.lowered_max_score().unwrap();
+                assert_eq!(lowered, max);
+            }
+            assert_eq!(game.lowered_max_score(), None);
+            maxes.push(max);
+        }
+        assert_eq!(game.max_score(), game.score());
+    }
+}
+
+#[test]
+fn max_score_counts_turns_before_the_last_round() {
+    use Color::*;
+    use Number::*;
+
+    // red can get to five and blue stays at one, but there's only one card left in the deck
+    let mut game = Game::builder(["a", "b"])
+        .hand(0, [(Red, Three), (Blue, One)])
+        .hand(1, [(Red, Four)])
+        .deck([(Red, Five)])
+        .played(Red, Two)
+        .played(Blue, One)
+        .clues(4)
+        .build()
+        .unwrap();
+    assert_eq!(game.max_score(), 6);
+    assert_eq!(game.pace(), 0);
+
+    // so throwing away even a useless card costs a point
+    game.apply(Action::Discard(1)).unwrap();
+    assert_eq!(game.max_score(), 5);
+    assert_eq!(
+        game.lowered_max_score(),
+        Some((5, "not enough turns left to play every card".to_string()))
+    );
+}
+
+#[test]
+fn pace_and_efficiency() {
+    let mut game = Game::with_rng(["a", "b", "c", "d", "e"], &mut StdRng::seed_from_u64(1));
+    // 30 cards in the deck, plus a final turn for each of the 5 players, to get 25 points
+    assert_eq!(game.pace(), 10);
+    assert_eq!(game.efficiency(), None);
+
+    let clue = game
+        .legal_actions()
+        .into_iter()
+        .find(|a| matches!(a, Action::Clue { .. }))
+        .unwrap();
+    game.apply(clue).unwrap();
+    assert_eq!(game.pace(), 10);
+    assert_eq!(game.efficiency(), Some(0.0));
+
+    // discarding uses up a turn without scoring (and may lower the max score, too)
+    let max = game.max_score();
+    game.apply(Action::Discard(0)).unwrap();
+    assert_eq!(game.pace(), 9 + (max - game.max_score()) as isize);
+}
+
 #[derive(Debug, Default)]
 struct DummyMessageProxy {
     msgs: HashMap<String, Vec<String>>,